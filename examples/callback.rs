@@ -0,0 +1,42 @@
+pub use realsense_wrapper::*;
+use std::time::Duration;
+
+fn main() {
+    if let Some(_) = callback_example() {
+        print!("callback example passed")
+    } else {
+        println!("callback example failed");
+    }
+}
+
+//Framesets arrive on a channel instead of blocking on wait_for_frames
+fn callback_example() -> Option<bool> {
+    let mut realsense = RealsenseInstance::try_new().ok()?;
+
+    let config = PipelineConfig::new().enable_stream(
+        stream::Rs2StreamKind::Depth,
+        0,
+        640,
+        480,
+        format::Rs2Format::Z16,
+        30,
+    );
+    let frames = realsense.start_with_channel(&config, 8).ok()?;
+
+    //Only the latest few frames matter, anything older gets dropped
+    let buffer = FrameBuffer::with_capacity(8, DropPolicy::DropOldest);
+    for _ in 0..30 {
+        let frameset = frames.recv_timeout(Duration::from_secs(5)).ok()?;
+        buffer.push_frames(frameset);
+    }
+    realsense.stop();
+    println!("{:?}", buffer.stats());
+
+    let mut received = 0;
+    while let Some(_) = buffer.get_curr_frame() {
+        received += 1;
+    }
+    println!("received {} frames", received);
+
+    return Some(received > 0);
+}
@@ -0,0 +1,85 @@
+pub use realsense_wrapper::*;
+
+fn main() {
+    if let Some(_) = color_to_depth_example() {
+        print!("color to depth example passed")
+    } else {
+        println!("color to depth example failed");
+    }
+}
+
+//Finds the depth pixel behind the center of the color image, like clicking on it in a UI
+fn color_to_depth_example() -> Option<bool> {
+    let mut realsense = RealsenseInstance::try_new().ok()?;
+
+    let depth_scale = realsense
+        .device
+        .find_sensor(extension::Rs2SensorExtension::DepthSensor)
+        .ok()??
+        .as_depth_sensor()
+        .ok()??
+        .depth_scale()
+        .ok()?;
+
+    let config = PipelineConfig::new()
+        .enable_stream(
+            stream::Rs2StreamKind::Depth,
+            0,
+            640,
+            480,
+            format::Rs2Format::Z16,
+            30,
+        )
+        .enable_stream(
+            stream::Rs2StreamKind::Color,
+            0,
+            1280,
+            720,
+            format::Rs2Format::RGB8,
+            30,
+        );
+
+    let pipeline_profile = realsense.start(&config).ok()?;
+    let streams = pipeline_profile.streams().ok()?;
+    let depth = streams
+        .iter()
+        .find(|profile| profile.stream == stream::Rs2StreamKind::Depth)?;
+    let color = streams
+        .iter()
+        .find(|profile| profile.stream == stream::Rs2StreamKind::Color)?;
+
+    let color_to_depth = ColorToDepth {
+        depth_scale,
+        depth_min: 0.1,
+        depth_max: 10.0,
+        depth_intrinsics: pipeline_profile.intrinsics(depth).ok()??,
+        color_intrinsics: pipeline_profile.intrinsics(color).ok()??,
+        color_to_depth: pipeline_profile.extrinsics(color, depth).ok()?,
+        depth_to_color: pipeline_profile.extrinsics(depth, color).ok()?,
+    };
+
+    let buffer = FrameBuffer::new();
+    buffer.populate_queue(&mut realsense).ok()?;
+
+    while let Some(image_data) = buffer.get_curr_frame() {
+        if let Some(depth_data) = image_data.depth_data() {
+            let pixel = [640.0, 360.0];
+
+            let rust = color_to_depth.color_pixel_to_depth_pixel(&depth_data, pixel);
+            let c = color_to_depth.rs2_color_pixel_to_depth_pixel(&depth_data, pixel);
+            println!(
+                "color pixel {:?} is depth pixel {:?} ({:?} from librealsense)",
+                pixel, rust, c
+            );
+
+            let [x, y] = rust?;
+            println!(
+                "{:.3} meters away",
+                image_data.distance_at(x as usize, y as usize)?
+            );
+            return Some(true);
+        }
+    }
+
+    return None;
+}
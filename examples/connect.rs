@@ -9,8 +9,15 @@ fn main() {
 }
 
 fn device_info_example() -> Option<bool> {
-    //Very simple example this will just create a realsense object and then connect to it
-    let realsense = RealsenseInstance::new();
+    //Very simple example this will list every connected realsense and then connect to the first one
+    let context = Context::new().ok()?;
+    let devices = context.devices().ok()?;
+
+    for device in &devices {
+        println!("{:?}", device);
+    }
+
+    let realsense = RealsenseInstance::open(context, devices.first()?).ok()?;
 
     //If this code crashes then you do not have a realsense plugged in or I royally borked something
     return Some(true);
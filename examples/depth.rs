@@ -18,9 +18,11 @@ fn black_white_example() -> Option<bool> {
     let stream = stream::Rs2StreamKind::Depth;
     let format = format::Rs2Format::Z16;
 
-    realsense.stream_frames(stream_index, width, height, fps, stream, format);
+    realsense
+        .stream_frames(stream_index, width, height, fps, stream, format)
+        .ok()?;
 
-    let mut buffer = FrameBuffer::new();
+    let buffer = FrameBuffer::new();
     buffer.populate_queue(&mut realsense);
 
     let image = buffer.get_curr_frame();
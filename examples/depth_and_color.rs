@@ -0,0 +1,78 @@
+pub use realsense_wrapper::*;
+
+fn main() {
+    if let Some(_) = depth_and_color_example() {
+        print!("depth and color example passed")
+    } else {
+        println!("depth and color example failed");
+    }
+}
+
+fn depth_and_color_example() -> Option<bool> {
+    let mut realsense = RealsenseInstance::try_new().ok()?;
+
+    let config = PipelineConfig::new()
+        .enable_stream(
+            stream::Rs2StreamKind::Depth,
+            0,
+            640,
+            480,
+            format::Rs2Format::Z16,
+            30,
+        )
+        .enable_stream(
+            stream::Rs2StreamKind::Color,
+            0,
+            640,
+            480,
+            format::Rs2Format::RGB8,
+            30,
+        );
+
+    let pipeline_profile = realsense.start(&config).ok()?;
+    let streams = pipeline_profile.streams().ok()?;
+
+    for profile in &streams {
+        println!(
+            "{:?} {:?}",
+            profile.stream,
+            pipeline_profile.intrinsics(profile).ok()?
+        );
+    }
+
+    //How to get from depth coordinates to color coordinates
+    let depth = streams
+        .iter()
+        .find(|profile| profile.stream == stream::Rs2StreamKind::Depth)?;
+    let color = streams
+        .iter()
+        .find(|profile| profile.stream == stream::Rs2StreamKind::Color)?;
+    println!("{:?}", pipeline_profile.extrinsics(depth, color).ok()?);
+
+    //Depth and color of the same frameset belong together
+    let frameset = realsense.wait_for_frames(RS2_DEFAULT_TIMEOUT).ok()?;
+    println!(
+        "depth {:?} color {:?}",
+        frameset
+            .depth()
+            .and_then(|frame| frame.stream_profile().ok()),
+        frameset
+            .color()
+            .and_then(|frame| frame.stream_profile().ok()),
+    );
+
+    let buffer = FrameBuffer::new();
+    buffer.populate_queue(&mut realsense).ok()?;
+
+    let mut saved = 0;
+    while let Some(image_data) = buffer.get_curr_frame() {
+        if let Some(saved_pic) = image_data.to_image() {
+            saved_pic
+                .save(format!("depth_and_color_example_{}.png", saved))
+                .ok()?;
+            saved += 1;
+        }
+    }
+
+    return Some(saved > 0);
+}
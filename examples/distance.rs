@@ -0,0 +1,49 @@
+pub use realsense_wrapper::*;
+
+fn main() {
+    if let Some(_) = distance_example() {
+        print!("distance example passed")
+    } else {
+        println!("distance example failed");
+    }
+}
+
+//Rust take on realsense_c_examples/distance.c
+fn distance_example() -> Option<bool> {
+    let mut realsense = RealsenseInstance::try_new().ok()?;
+
+    let depth = realsense
+        .device
+        .find_sensor(extension::Rs2SensorExtension::DepthSensor)
+        .ok()??;
+    let scale = depth.as_depth_sensor().ok()??.depth_scale().ok()?;
+    println!("depth scale: {} meters per unit", scale);
+
+    let width = 640;
+    let height = 480;
+    realsense
+        .stream_frames(
+            0,
+            width,
+            height,
+            30,
+            stream::Rs2StreamKind::Depth,
+            format::Rs2Format::Z16,
+        )
+        .ok()?;
+
+    let buffer = FrameBuffer::new();
+    buffer.populate_queue(&mut realsense).ok()?;
+
+    let image_data = buffer.get_curr_frame()?;
+    let distance = image_data.distance_at(width as usize / 2, height as usize / 2)?;
+    println!("The camera is facing an object {:.3} meters away", distance);
+
+    let meters = image_data.depth_meters()?;
+    println!(
+        "furthest point: {:.3} meters",
+        meters.fold(0.0f32, |a, b| a.max(*b))
+    );
+
+    return Some(true);
+}
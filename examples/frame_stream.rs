@@ -0,0 +1,61 @@
+use futures_core::Stream;
+pub use realsense_wrapper::*;
+use std::{
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll, Wake},
+    thread::{self, Thread},
+};
+
+fn main() {
+    if let Some(_) = frame_stream_example() {
+        print!("frame stream example passed")
+    } else {
+        println!("frame stream example failed");
+    }
+}
+
+//Any async runtime works, this parks the thread between frames to keep the example free of one
+struct ThreadWaker(Thread);
+
+impl Wake for ThreadWaker {
+    fn wake(self: Arc<Self>) {
+        self.0.unpark();
+    }
+}
+
+fn frame_stream_example() -> Option<bool> {
+    let realsense = RealsenseInstance::try_new().ok()?;
+
+    let config = PipelineConfig::new().enable_stream(
+        stream::Rs2StreamKind::Depth,
+        0,
+        640,
+        480,
+        format::Rs2Format::Z16,
+        30,
+    );
+    let mut frames = realsense.frame_stream(&config, 4).ok()?;
+
+    let waker = Arc::new(ThreadWaker(thread::current())).into();
+    let mut cx = Context::from_waker(&waker);
+
+    let buffer = FrameBuffer::new();
+    let mut received = 0;
+    while received < 30 {
+        match Pin::new(&mut frames).poll_next(&mut cx) {
+            Poll::Ready(Some(frameset)) => {
+                buffer.push_frames(frameset.ok()?);
+                received += 1;
+            }
+            Poll::Ready(None) => return None,
+            Poll::Pending => thread::park(),
+        }
+    }
+
+    //Dropping the stream stops the pipeline
+    drop(frames);
+
+    println!("received {} framesets", received);
+    return Some(buffer.get_curr_frame().is_some());
+}
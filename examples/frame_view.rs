@@ -0,0 +1,64 @@
+pub use realsense_wrapper::*;
+
+fn main() {
+    if let Some(_) = frame_view_example() {
+        print!("frame view example passed")
+    } else {
+        println!("frame view example failed");
+    }
+}
+
+fn frame_view_example() -> Option<bool> {
+    let mut realsense = RealsenseInstance::try_new().ok()?;
+
+    let config = PipelineConfig::new()
+        .enable_stream(
+            stream::Rs2StreamKind::Depth,
+            0,
+            848,
+            480,
+            format::Rs2Format::Z16,
+            90,
+        )
+        .enable_stream(
+            stream::Rs2StreamKind::Color,
+            0,
+            848,
+            480,
+            format::Rs2Format::RGB8,
+            60,
+        );
+    realsense.start(&config).ok()?;
+
+    //Kept around without copying, each clone is only another reference on the frame
+    let mut kept = Vec::new();
+
+    for _ in 0..90 {
+        let frameset = realsense.wait_for_frames(RS2_DEFAULT_TIMEOUT).ok()?;
+
+        if let Some(depth) = frameset.depth() {
+            let view = depth.view().ok()?;
+            let depth_data = view.as_u16_array2()?;
+
+            let valid = depth_data.iter().filter(|depth| **depth != 0).count();
+            println!("{} of {} pixels have depth", valid, depth_data.len());
+
+            kept.push(depth.clone());
+        }
+
+        if let Some(color) = frameset.color() {
+            let view = color.view().ok()?;
+            let pixels = view.as_array3()?;
+
+            let center = pixels.slice(ndarray::s![view.height / 2, view.width / 2, ..]);
+            println!("center pixel {:?}", center.to_vec());
+        }
+
+        //Frames go back to librealsense's pool once released
+        if kept.len() > 4 {
+            kept.remove(0);
+        }
+    }
+
+    return Some(!kept.is_empty());
+}
@@ -0,0 +1,24 @@
+pub use realsense_wrapper::*;
+
+fn main() {
+    if let Some(_) = hotplug_example() {
+        print!("hotplug example passed")
+    } else {
+        println!("hotplug example failed");
+    }
+}
+
+fn hotplug_example() -> Option<bool> {
+    //Plug and unplug a realsense while this runs, every change gets printed
+    let mut context = Context::new().ok()?;
+    let events = context.device_events().ok()?;
+
+    for event in events.iter().take(4) {
+        match event {
+            DeviceEvent::Added(info) => println!("Added {:?}", info),
+            DeviceEvent::Removed(info) => println!("Removed {:?}", info),
+        }
+    }
+
+    return Some(true);
+}
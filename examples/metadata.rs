@@ -0,0 +1,43 @@
+pub use realsense_wrapper::*;
+
+fn main() {
+    if let Some(_) = metadata_example() {
+        print!("metadata example passed")
+    } else {
+        println!("metadata example failed");
+    }
+}
+
+fn metadata_example() -> Option<bool> {
+    let mut realsense = RealsenseInstance::try_new().ok()?;
+
+    realsense
+        .stream_frames(
+            0,
+            640,
+            480,
+            30,
+            stream::Rs2StreamKind::Depth,
+            format::Rs2Format::Z16,
+        )
+        .ok()?;
+
+    let buffer = FrameBuffer::new();
+    for _ in 0..10 {
+        buffer.populate_queue(&mut realsense).ok()?;
+    }
+
+    while let Some(image_data) = buffer.get_curr_frame() {
+        let metadata = image_data.frame_info().metadata;
+        println!(
+            "frame {:?} exposure {:?} gain {:?} laser power {:?} emitter {:?}",
+            metadata.frame_counter,
+            metadata.actual_exposure,
+            metadata.gain_level,
+            metadata.laser_power,
+            metadata.emitter_mode
+        );
+    }
+
+    return Some(true);
+}
@@ -0,0 +1,55 @@
+pub use realsense_wrapper::*;
+
+fn main() {
+    if let Some(_) = options_example() {
+        print!("options example passed")
+    } else {
+        println!("options example failed");
+    }
+}
+
+fn options_example() -> Option<bool> {
+    let realsense = RealsenseInstance::try_new().ok()?;
+
+    for sensor in realsense.device.sensors().ok()? {
+        println!("{:?}", sensor.name().ok()?);
+
+        for option in sensor.supported_options().ok()? {
+            println!(
+                "    {} = {} {:?} read only: {} ({})",
+                option,
+                sensor.get_option(option).ok()?,
+                sensor.option_range(option).ok()?,
+                sensor.is_read_only(option).ok()?,
+                sensor.option_description(option).ok()?
+            );
+        }
+    }
+
+    let depth = realsense
+        .device
+        .find_sensor(extension::Rs2SensorExtension::DepthSensor)
+        .ok()??;
+
+    //D400 cameras only, other families have their own preset enums
+    if depth
+        .supports_option(option::Rs2Option::VisualPreset)
+        .ok()?
+    {
+        let applied = depth
+            .apply_visual_preset(visual_preset::Rs400VisualPreset::HighAccuracy)
+            .ok()?;
+        println!("visual preset is now {:?}", applied);
+    }
+
+    //Manual exposure in the middle of whatever range the device allows
+    let range = depth.option_range(option::Rs2Option::Exposure).ok()?;
+    depth
+        .set_option(option::Rs2Option::EnableAutoExposure, 0.0)
+        .ok()?;
+    depth
+        .set_option(option::Rs2Option::Exposure, (range.min + range.max) / 2.0)
+        .ok()?;
+
+    return Some(true);
+}
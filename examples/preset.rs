@@ -0,0 +1,33 @@
+pub use realsense_wrapper::*;
+
+fn main() {
+    if let Some(_) = preset_example() {
+        print!("preset example passed")
+    } else {
+        println!("preset example failed");
+    }
+}
+
+//Run with no arguments to export the current advanced mode preset, or with a preset file to upload it
+fn preset_example() -> Option<bool> {
+    let realsense = RealsenseInstance::try_new().ok()?;
+
+    match std::env::args().nth(1) {
+        Some(path) => {
+            let json = std::fs::read_to_string(path).ok()?;
+            let preset = AdvancedModePreset::from_json(&json).ok()?;
+
+            if let Err(err) = realsense.device.load_preset(&preset) {
+                println!("{}", err);
+                return None;
+            }
+        }
+        None => {
+            let preset = realsense.device.export_preset().ok()?;
+            println!("{:?}", preset.parameters);
+            std::fs::write("advanced_mode_preset.json", preset.to_json().ok()?).ok()?;
+        }
+    }
+
+    return Some(true);
+}
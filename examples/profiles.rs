@@ -0,0 +1,38 @@
+pub use realsense_wrapper::*;
+
+fn main() {
+    if let Some(_) = profiles_example() {
+        print!("profiles example passed")
+    } else {
+        println!("profiles example failed");
+    }
+}
+
+fn profiles_example() -> Option<bool> {
+    let mut realsense = RealsenseInstance::try_new().ok()?;
+    let profiles = realsense.device.stream_profiles().ok()?;
+
+    let color = StreamProfileQuery::new()
+        .stream(stream::Rs2StreamKind::Color)
+        .format(format::Rs2Format::RGB8);
+
+    for profile in color.filter(&profiles) {
+        println!(
+            "{}x{} @ {} fps",
+            profile.width, profile.height, profile.frame_rate
+        );
+    }
+
+    //Whatever the camera supports that is nearest to 1000x700 at 25 fps
+    let closest = color
+        .resolution(1000, 700)
+        .frame_rate(25)
+        .closest(&profiles)?;
+    println!("Closest match {:?}", closest);
+
+    realsense
+        .start(&PipelineConfig::new().enable_profile(&closest))
+        .ok()?;
+
+    return Some(true);
+}
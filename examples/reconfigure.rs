@@ -0,0 +1,45 @@
+pub use realsense_wrapper::*;
+
+fn main() {
+    if let Some(_) = reconfigure_example() {
+        print!("reconfigure example passed")
+    } else {
+        println!("reconfigure example failed");
+    }
+}
+
+fn color_config(width: i32, height: i32, fps: i32) -> PipelineConfig {
+    PipelineConfig::new().enable_stream(
+        stream::Rs2StreamKind::Color,
+        0,
+        width,
+        height,
+        format::Rs2Format::RGB8,
+        fps,
+    )
+}
+
+fn reconfigure_example() -> Option<bool> {
+    let mut realsense = RealsenseInstance::try_new().ok()?;
+    let buffer = FrameBuffer::new();
+
+    realsense.start(&color_config(640, 480, 30)).ok()?;
+    buffer.populate_queue(&mut realsense).ok()?;
+    buffer
+        .get_curr_frame()?
+        .to_image()?
+        .save("reconfigure_small.png")
+        .ok()?;
+
+    //Same context and device, only the stream changes
+    realsense.reconfigure(&color_config(1280, 720, 15)).ok()?;
+    buffer.populate_queue(&mut realsense).ok()?;
+    buffer
+        .get_curr_frame()?
+        .to_image()?
+        .save("reconfigure_large.png")
+        .ok()?;
+
+    realsense.stop();
+    return Some(!realsense.is_streaming());
+}
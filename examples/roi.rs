@@ -0,0 +1,41 @@
+pub use realsense_wrapper::*;
+
+fn main() {
+    if let Some(_) = roi_example() {
+        print!("roi example passed")
+    } else {
+        println!("roi example failed");
+    }
+}
+
+//Auto exposure only looks at the bottom half of the image, e.g. to ignore a bright ceiling
+fn roi_example() -> Option<bool> {
+    let mut realsense = RealsenseInstance::try_new().ok()?;
+
+    let config = PipelineConfig::new().enable_stream(
+        stream::Rs2StreamKind::Depth,
+        0,
+        640,
+        480,
+        format::Rs2Format::Z16,
+        30,
+    );
+    realsense.start(&config).ok()?;
+
+    let depth = realsense
+        .device
+        .find_sensor(extension::Rs2SensorExtension::DepthSensor)
+        .ok()??;
+    let roi_sensor = depth.as_roi_sensor().ok()??;
+
+    let (width, height) = roi_sensor.active_resolution().ok()??;
+    let roi = RegionOfInterest::new(0, height / 2, width - 1, height - 1);
+
+    if let Err(err) = roi_sensor.set_region_of_interest(roi) {
+        println!("{}", err);
+        return None;
+    }
+    println!("{:?}", roi_sensor.region_of_interest().ok()?);
+
+    return Some(true);
+}
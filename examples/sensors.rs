@@ -0,0 +1,27 @@
+pub use realsense_wrapper::*;
+
+fn main() {
+    if let Some(_) = sensors_example() {
+        print!("sensors example passed")
+    } else {
+        println!("sensors example failed");
+    }
+}
+
+fn sensors_example() -> Option<bool> {
+    let realsense = RealsenseInstance::try_new().ok()?;
+
+    for sensor in realsense.device.sensors().ok()? {
+        println!(
+            "{:?} depth: {:?} color: {:?} motion: {:?} roi: {:?} profiles: {}",
+            sensor.name().ok()?,
+            sensor.is_depth_sensor().ok()?,
+            sensor.is_color_sensor().ok()?,
+            sensor.is_motion_sensor().ok()?,
+            sensor.is_roi_sensor().ok()?,
+            sensor.stream_profiles().ok()?.len()
+        );
+    }
+
+    return Some(true);
+}
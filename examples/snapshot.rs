@@ -0,0 +1,31 @@
+pub use realsense_wrapper::*;
+
+fn main() {
+    if let Some(_) = snapshot_example() {
+        print!("snapshot example passed")
+    } else {
+        println!("snapshot example failed");
+    }
+}
+
+//Run with no arguments to save the current settings, or with a file to restore them
+fn snapshot_example() -> Option<bool> {
+    let realsense = RealsenseInstance::try_new().ok()?;
+
+    match std::env::args().nth(1) {
+        Some(path) => {
+            let json = std::fs::read_to_string(path).ok()?;
+            let snapshot = OptionsSnapshot::from_json(&json).ok()?;
+
+            for failure in realsense.device.apply_snapshot(&snapshot).ok()? {
+                println!("{}", failure);
+            }
+        }
+        None => {
+            let snapshot = realsense.device.options_snapshot().ok()?;
+            std::fs::write("options_snapshot.json", snapshot.to_json().ok()?).ok()?;
+        }
+    }
+
+    return Some(true);
+}
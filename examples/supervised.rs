@@ -0,0 +1,44 @@
+pub use realsense_wrapper::*;
+
+fn main() {
+    if let Some(_) = supervised_example() {
+        print!("supervised example passed")
+    } else {
+        println!("supervised example failed");
+    }
+}
+
+fn supervised_example() -> Option<bool> {
+    let mut realsense = RealsenseInstance::try_new().ok()?;
+
+    let stream_index = 0;
+    let width = 640;
+    let height = 480;
+    let fps = 30;
+    let stream = stream::Rs2StreamKind::Color;
+    let format = format::Rs2Format::RGB8;
+
+    realsense
+        .stream_frames(stream_index, width, height, fps, stream, format)
+        .ok()?;
+
+    //Unplug the camera while this runs, it should pick back up once it is plugged in again
+    let mut supervisor = realsense.supervise(RecoveryPolicy::default()).ok()?;
+    let buffer = FrameBuffer::new();
+
+    for _ in 0..300 {
+        match supervisor.next_frame() {
+            Ok(frames) => {
+                buffer.push_frames(frames);
+            }
+            Err(err) => println!("{}", err),
+        }
+
+        if let Some(image_data) = buffer.get_curr_frame() {
+            image_data.to_image()?;
+        }
+    }
+
+    println!("Recovered {} times", supervisor.recoveries());
+    return Some(true);
+}
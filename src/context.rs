@@ -0,0 +1,235 @@
+use crate::{bindings::*, check_error, Device, DeviceInfo, RealsenseError};
+use std::{
+    os::raw::c_void,
+    panic::{self, AssertUnwindSafe},
+    sync::mpsc,
+};
+
+pub struct Context {
+    pub context: *mut rs2_context,
+    //Every callback state ever registered. A callback already running on librealsense's thread can still be
+    //using a replaced one, so none of them are freed before the context is deleted
+    devices_changed: Vec<Box<DevicesChanged>>,
+}
+
+unsafe impl Sync for Context {}
+
+unsafe impl Send for Context {}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum DeviceEvent {
+    Added(DeviceInfo),
+    Removed(DeviceInfo),
+}
+
+//Handed to librealsense as the user pointer of the devices changed callback.
+//known keeps a device handle for everything connected so removals can be matched with rs2_device_list_contains
+struct DevicesChanged {
+    known: Vec<(DeviceInfo, Device)>,
+    callback: Box<dyn FnMut(DeviceEvent) + Send>,
+}
+
+impl DevicesChanged {
+    unsafe fn on_devices_changed(
+        &mut self,
+        removed: *mut rs2_device_list,
+        added: *mut rs2_device_list,
+    ) -> Result<(), RealsenseError> {
+        let mut error = std::ptr::null_mut::<rs2_error>();
+
+        let mut still_connected = Vec::with_capacity(self.known.len());
+        for (info, device) in self.known.drain(..) {
+            let was_removed = rs2_device_list_contains(removed, device.device, &mut error);
+            check_error(error)?;
+
+            if was_removed != 0 {
+                (self.callback)(DeviceEvent::Removed(info));
+            } else {
+                still_connected.push((info, device));
+            }
+        }
+        self.known = still_connected;
+
+        for (info, device) in devices_in_list(added)? {
+            (self.callback)(DeviceEvent::Added(info.clone()));
+            self.known.push((info, device));
+        }
+
+        rs2_free_error(error);
+        return Ok(());
+    }
+}
+
+unsafe extern "C" fn devices_changed_callback(
+    removed: *mut rs2_device_list,
+    added: *mut rs2_device_list,
+    user: *mut c_void,
+) {
+    //Panicking across the FFI boundary aborts, so anything the user closure throws stops here
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        (*user.cast::<DevicesChanged>()).on_devices_changed(removed, added)
+    }));
+
+    match result {
+        Ok(Err(err)) => log::warn!("Failed to handle devices changed event: {}", err),
+        Err(_) => log::error!("Devices changed callback panicked"),
+        Ok(Ok(())) => {}
+    }
+
+    //Both lists are owned by the callback
+    rs2_delete_device_list(removed);
+    rs2_delete_device_list(added);
+}
+
+//Registered in place of the user callback on drop, librealsense does not accept a null callback
+unsafe extern "C" fn ignore_devices_changed(
+    removed: *mut rs2_device_list,
+    added: *mut rs2_device_list,
+    _user: *mut c_void,
+) {
+    rs2_delete_device_list(removed);
+    rs2_delete_device_list(added);
+}
+
+//Creates a device for every entry of the list, the list itself is left for the caller to delete
+unsafe fn devices_in_list(
+    device_list: *const rs2_device_list,
+) -> Result<Vec<(DeviceInfo, Device)>, RealsenseError> {
+    let mut error = std::ptr::null_mut::<rs2_error>();
+
+    let device_count = rs2_get_device_count(device_list, &mut error);
+    check_error(error)?;
+
+    let mut devices = Vec::with_capacity(device_count as usize);
+
+    for index in 0..device_count {
+        let device = rs2_create_device(device_list, index, &mut error);
+        check_error(error)?;
+
+        let device = Device { device };
+        devices.push((DeviceInfo::new(&device, index)?, device));
+    }
+
+    rs2_free_error(error);
+    return Ok(devices);
+}
+
+impl Context {
+    pub fn new() -> Result<Context, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let context = rs2_create_context(RS2_API_VERSION as i32, &mut error);
+            check_error(error)?;
+
+            rs2_free_error(error);
+            return Ok(Context {
+                context,
+                devices_changed: Vec::new(),
+            });
+        }
+    }
+
+    unsafe fn query_devices(&self) -> Result<Vec<(DeviceInfo, Device)>, RealsenseError> {
+        let mut error = std::ptr::null_mut::<rs2_error>();
+
+        let device_list = rs2_query_devices(self.context, &mut error);
+        check_error(error)?;
+
+        //devices created from the list outlive it
+        let devices = devices_in_list(device_list);
+        rs2_delete_device_list(device_list);
+
+        rs2_free_error(error);
+        return devices;
+    }
+
+    //Snapshot of every device connected right now, in the order librealsense enumerates them
+    pub fn devices(&self) -> Result<Vec<DeviceInfo>, RealsenseError> {
+        unsafe {
+            return Ok(self
+                .query_devices()?
+                .into_iter()
+                .map(|(info, _)| info)
+                .collect());
+        }
+    }
+
+    //The info should come from devices() on this same context, the index is not stable across contexts
+    pub fn open_device(&self, info: &DeviceInfo) -> Result<Device, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let device_list = rs2_query_devices(self.context, &mut error);
+            check_error(error)?;
+
+            let device = rs2_create_device(device_list, info.index, &mut error);
+            rs2_delete_device_list(device_list); //devices created from the list outlive it
+            check_error(error)?;
+
+            rs2_free_error(error);
+            return Ok(Device { device });
+        }
+    }
+
+    //The callback runs on a librealsense thread every time a device is plugged in or unplugged.
+    //Registering again replaces the previous callback
+    pub fn set_devices_changed_callback<F>(&mut self, callback: F) -> Result<(), RealsenseError>
+    where
+        F: FnMut(DeviceEvent) + Send + 'static,
+    {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let mut devices_changed = Box::new(DevicesChanged {
+                known: self.query_devices()?,
+                callback: Box::new(callback),
+            });
+
+            rs2_set_devices_changed_callback(
+                self.context,
+                Some(devices_changed_callback),
+                (devices_changed.as_mut() as *mut DevicesChanged).cast::<c_void>(),
+                &mut error,
+            );
+            check_error(error)?;
+
+            self.devices_changed.push(devices_changed);
+
+            rs2_free_error(error);
+            return Ok(());
+        }
+    }
+
+    pub fn device_events(&mut self) -> Result<mpsc::Receiver<DeviceEvent>, RealsenseError> {
+        let (sender, receiver) = mpsc::channel();
+
+        self.set_devices_changed_callback(move |event| {
+            //Nobody is listening anymore, nothing to do
+            let _ = sender.send(event);
+        })?;
+
+        return Ok(receiver);
+    }
+}
+
+impl Drop for Context {
+    fn drop(&mut self) {
+        unsafe {
+            //Devices can keep the underlying context alive, so make sure it stops calling into state we are about to free
+            if !self.devices_changed.is_empty() {
+                let mut error = std::ptr::null_mut::<rs2_error>();
+                rs2_set_devices_changed_callback(
+                    self.context,
+                    Some(ignore_devices_changed),
+                    std::ptr::null_mut(),
+                    &mut error,
+                );
+                rs2_free_error(error);
+            }
+
+            rs2_delete_context(self.context);
+        }
+        //devices_changed is dropped only now, after the context is gone
+    }
+}
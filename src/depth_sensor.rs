@@ -0,0 +1,21 @@
+use crate::{bindings::*, check_error, RealsenseError, Sensor};
+
+//A sensor that produces depth frames, see Sensor::as_depth_sensor
+pub struct DepthSensor<'a> {
+    pub sensor: &'a Sensor,
+}
+
+impl<'a> DepthSensor<'a> {
+    //Meters per unit of the Z16 values this sensor streams, usually 0.001
+    pub fn depth_scale(&self) -> Result<f32, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let scale = rs2_get_depth_scale(self.sensor.sensor, &mut error);
+            check_error(error)?;
+
+            rs2_free_error(error);
+            return Ok(scale);
+        }
+    }
+}
@@ -0,0 +1,289 @@
+use crate::{
+    bindings::*, camera_info::Rs2CameraInfo, check_error, extension::Rs2SensorExtension,
+    option::Rs2Option, AdvancedModePreset, OptionFailure, OptionFailureReason, OptionsSnapshot,
+    RealsenseError, Sensor, SensorSnapshot, StreamProfile,
+};
+use std::{collections::BTreeMap, ffi::CStr, os::raw::c_void, slice};
+
+pub struct Device {
+    pub device: *mut rs2_device,
+}
+
+unsafe impl Sync for Device {}
+
+unsafe impl Send for Device {}
+
+impl Device {
+    //Returns None when the device does not report this kind of info
+    pub fn info(&self, info: Rs2CameraInfo) -> Result<Option<String>, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let supported = rs2_supports_device_info(self.device, info as u32, &mut error);
+            check_error(error)?;
+
+            if supported == 0 {
+                rs2_free_error(error);
+                return Ok(None);
+            }
+
+            let value = rs2_get_device_info(self.device, info as u32, &mut error);
+            check_error(error)?;
+
+            rs2_free_error(error);
+
+            return Ok(Some(CStr::from_ptr(value).to_string_lossy().into_owned()));
+        }
+    }
+
+    pub fn sensors(&self) -> Result<Vec<Sensor>, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let sensor_list = rs2_query_sensors(self.device, &mut error);
+            check_error(error)?;
+
+            let sensor_count = rs2_get_sensors_count(sensor_list, &mut error);
+            if let Err(err) = check_error(error) {
+                rs2_delete_sensor_list(sensor_list);
+                return Err(err);
+            }
+
+            let mut sensors = Vec::with_capacity(sensor_count as usize);
+            for index in 0..sensor_count {
+                let sensor = rs2_create_sensor(sensor_list, index, &mut error);
+                if let Err(err) = check_error(error) {
+                    rs2_delete_sensor_list(sensor_list);
+                    return Err(err);
+                }
+
+                sensors.push(Sensor { sensor });
+            }
+
+            //sensors created from the list outlive it
+            rs2_delete_sensor_list(sensor_list);
+            rs2_free_error(error);
+            return Ok(sensors);
+        }
+    }
+
+    //First sensor that can be extended to the given extension, if any
+    pub fn find_sensor(
+        &self,
+        extension: Rs2SensorExtension,
+    ) -> Result<Option<Sensor>, RealsenseError> {
+        for sensor in self.sensors()? {
+            if sensor.is_extendable_to(extension)? {
+                return Ok(Some(sensor));
+            }
+        }
+
+        return Ok(None);
+    }
+
+    //Every mode any of the device's sensors can stream, filter it with a StreamProfileQuery
+    pub fn stream_profiles(&self) -> Result<Vec<StreamProfile>, RealsenseError> {
+        let mut profiles = Vec::new();
+
+        for sensor in self.sensors()? {
+            profiles.extend(sensor.stream_profiles()?);
+        }
+
+        return Ok(profiles);
+    }
+}
+
+//Option snapshots
+impl Device {
+    //Read only options like temperatures are telemetry rather than settings, so they are left out.
+    //Options that can not be read right now are skipped instead of failing the whole snapshot
+    pub fn options_snapshot(&self) -> Result<OptionsSnapshot, RealsenseError> {
+        let mut sensors = Vec::new();
+
+        for sensor in self.sensors()? {
+            let name = sensor.name()?.unwrap_or_default();
+            let mut options = BTreeMap::new();
+
+            for option in sensor.supported_options()? {
+                if sensor.is_read_only(option)? {
+                    continue;
+                }
+
+                match sensor.get_option(option) {
+                    Ok(value) => {
+                        options.insert(option, value);
+                    }
+                    Err(err) => log::warn!("Skipping {} on {}: {}", option, name, err),
+                }
+            }
+
+            sensors.push(SensorSnapshot { name, options });
+        }
+
+        return Ok(OptionsSnapshot { sensors });
+    }
+
+    //Applies everything it can and reports the rest, only failing outright if the sensors can not be listed
+    pub fn apply_snapshot(
+        &self,
+        snapshot: &OptionsSnapshot,
+    ) -> Result<Vec<OptionFailure>, RealsenseError> {
+        let sensors = self.sensors()?;
+        let mut failures = Vec::new();
+
+        for sensor_snapshot in &snapshot.sensors {
+            let sensor = sensors.iter().find(|sensor| {
+                sensor.name().ok().flatten().as_deref() == Some(sensor_snapshot.name.as_str())
+            });
+
+            //A visual preset overwrites most of the other options, so it goes first and the rest lands on top of it
+            let (preset, rest): (Vec<_>, Vec<_>) = sensor_snapshot
+                .options
+                .iter()
+                .partition(|(option, _)| **option == Rs2Option::VisualPreset);
+
+            for (&option, &value) in preset.into_iter().chain(rest) {
+                let reason = match sensor {
+                    Some(sensor) => match apply_option(sensor, option, value) {
+                        Ok(reason) => reason,
+                        Err(err) => Some(OptionFailureReason::Failed(err)),
+                    },
+                    None => Some(OptionFailureReason::SensorMissing),
+                };
+
+                if let Some(reason) = reason {
+                    failures.push(OptionFailure {
+                        sensor: sensor_snapshot.name.clone(),
+                        option,
+                        value,
+                        reason,
+                    });
+                }
+            }
+        }
+
+        return Ok(failures);
+    }
+}
+
+//Advanced mode presets, the device has to be in advanced mode for librealsense to accept them
+impl Device {
+    pub fn load_preset_json(&self, json: &str) -> Result<(), RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            rs2_load_json(
+                self.device,
+                json.as_ptr().cast::<c_void>(),
+                json.len() as u32,
+                &mut error,
+            );
+            check_error(error)?;
+
+            rs2_free_error(error);
+            return Ok(());
+        }
+    }
+
+    pub fn export_preset_json(&self) -> Result<String, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let buffer = rs2_serialize_json(self.device, &mut error);
+            check_error(error)?;
+
+            let size = rs2_get_raw_data_size(buffer, &mut error);
+            if let Err(err) = check_error(error) {
+                rs2_delete_raw_data(buffer);
+                return Err(err);
+            }
+
+            let data = rs2_get_raw_data(buffer, &mut error);
+            if let Err(err) = check_error(error) {
+                rs2_delete_raw_data(buffer);
+                return Err(err);
+            }
+
+            let json =
+                String::from_utf8_lossy(slice::from_raw_parts(data, size as usize)).into_owned();
+            rs2_delete_raw_data(buffer);
+
+            rs2_free_error(error);
+            return Ok(json);
+        }
+    }
+
+    //Validated before anything is sent to the device
+    pub fn load_preset(&self, preset: &AdvancedModePreset) -> Result<(), RealsenseError> {
+        preset.validate().map_err(RealsenseError::InvalidPreset)?;
+
+        let json = preset
+            .to_json()
+            .map_err(|err| RealsenseError::InvalidPreset(vec![err.to_string()]))?;
+        return self.load_preset_json(&json);
+    }
+
+    pub fn export_preset(&self) -> Result<AdvancedModePreset, RealsenseError> {
+        let json = self.export_preset_json()?;
+        return AdvancedModePreset::from_json(&json)
+            .map_err(|err| RealsenseError::InvalidPreset(vec![err.to_string()]));
+    }
+}
+
+fn apply_option(
+    sensor: &Sensor,
+    option: Rs2Option,
+    value: f32,
+) -> Result<Option<OptionFailureReason>, RealsenseError> {
+    if !sensor.supports_option(option)? {
+        return Ok(Some(OptionFailureReason::Unsupported));
+    }
+
+    if sensor.is_read_only(option)? {
+        return Ok(Some(OptionFailureReason::ReadOnly));
+    }
+
+    let range = sensor.option_range(option)?;
+    if !range.contains(value) {
+        return Ok(Some(OptionFailureReason::OutOfRange(range)));
+    }
+
+    sensor.set_option(option, value)?;
+    return Ok(None);
+}
+
+impl Drop for Device {
+    fn drop(&mut self) {
+        unsafe {
+            rs2_delete_device(self.device);
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DeviceInfo {
+    //Position in the device list this was read from, only valid for the context that produced it
+    pub index: i32,
+    pub name: Option<String>,
+    pub serial_number: Option<String>,
+    pub firmware_version: Option<String>,
+    pub recommended_firmware_version: Option<String>,
+    pub product_line: Option<String>,
+    pub usb_type_descriptor: Option<String>,
+    pub physical_port: Option<String>,
+}
+
+impl DeviceInfo {
+    pub fn new(device: &Device, index: i32) -> Result<DeviceInfo, RealsenseError> {
+        return Ok(DeviceInfo {
+            index,
+            name: device.info(Rs2CameraInfo::Name)?,
+            serial_number: device.info(Rs2CameraInfo::SerialNumber)?,
+            firmware_version: device.info(Rs2CameraInfo::FirmwareVersion)?,
+            recommended_firmware_version: device.info(Rs2CameraInfo::RecommendedFirmwareVersion)?,
+            product_line: device.info(Rs2CameraInfo::ProductLine)?,
+            usb_type_descriptor: device.info(Rs2CameraInfo::UsbTypeDescriptor)?,
+            physical_port: device.info(Rs2CameraInfo::PhysicalPort)?,
+        });
+    }
+}
@@ -1,7 +1,10 @@
 use crate::bindings::*;
 use crate::types::format::Rs2Format;
+use crate::types::frame_metadata::Rs2FrameMetadata;
+use crate::types::stream::Rs2StreamKind;
 use crate::utils::*;
-use ndarray::Array2;
+use crate::{Extrinsics, Intrinsics, ProfileData, StreamProfile};
+use ndarray::{Array2, ArrayView2, ArrayView3, ShapeBuilder};
 use num_traits::FromPrimitive;
 use std::ffi::CStr;
 use std::mem::MaybeUninit;
@@ -13,6 +16,185 @@ pub struct SafeFrame {
     pub frame: *mut rs2_frame,
 }
 
+//Frames are reference counted by librealsense and only read from here, so they can move between threads
+unsafe impl Sync for SafeFrame {}
+
+unsafe impl Send for SafeFrame {}
+
+impl SafeFrame {
+    //None when the device or its firmware does not report this value for the frame
+    pub fn metadata(&self, key: Rs2FrameMetadata) -> Result<Option<i64>, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let supported = rs2_supports_frame_metadata(self.frame, key as u32, &mut error);
+            check_error(error)?;
+
+            if supported == 0 {
+                rs2_free_error(error);
+                return Ok(None);
+            }
+
+            let value = rs2_get_frame_metadata(self.frame, key as u32, &mut error);
+            check_error(error)?;
+
+            rs2_free_error(error);
+            return Ok(Some(value));
+        }
+    }
+
+    pub fn stream_profile(&self) -> Result<StreamProfile, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let profile = rs2_get_frame_stream_profile(self.frame, &mut error);
+            check_error(error)?;
+
+            rs2_free_error(error);
+            return StreamProfile::new(profile);
+        }
+    }
+
+    //Borrows the pixels in place instead of copying them like FrameData does
+    pub fn view(&self) -> Result<FrameView<'_>, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let data = rs2_get_frame_data(self.frame, &mut error);
+            check_error(error)?;
+
+            let data_size = rs2_get_frame_data_size(self.frame, &mut error);
+            check_error(error)?;
+
+            let profile = rs2_get_frame_stream_profile(self.frame, &mut error);
+            check_error(error)?;
+
+            //Only the format, StreamProfile::new also reads intrinsics, which is too much to do for every frame
+            let mut stream = MaybeUninit::uninit();
+            let mut format = MaybeUninit::uninit();
+            let mut index = MaybeUninit::uninit();
+            let mut unique_id = MaybeUninit::uninit();
+            let mut frame_rate = MaybeUninit::uninit();
+
+            rs2_get_stream_profile_data(
+                profile,
+                stream.as_mut_ptr(),
+                format.as_mut_ptr(),
+                index.as_mut_ptr(),
+                unique_id.as_mut_ptr(),
+                frame_rate.as_mut_ptr(),
+                &mut error,
+            );
+            check_error(error)?;
+
+            let is_video = rs2_is_frame_extendable_to(
+                self.frame,
+                rs2_extension_RS2_EXTENSION_VIDEO_FRAME,
+                &mut error,
+            );
+            check_error(error)?;
+
+            //Motion and pose frames are a single row of samples
+            let mut width = data_size;
+            let mut height = 1;
+            let mut stride = data_size;
+            let mut bits_per_pixel = BITS_IN_A_BYTE;
+            if is_video != 0 {
+                width = rs2_get_frame_width(self.frame, &mut error);
+                check_error(error)?;
+
+                height = rs2_get_frame_height(self.frame, &mut error);
+                check_error(error)?;
+
+                stride = rs2_get_frame_stride_in_bytes(self.frame, &mut error);
+                check_error(error)?;
+
+                bits_per_pixel = rs2_get_frame_bits_per_pixel(self.frame, &mut error);
+                check_error(error)?;
+            }
+
+            rs2_free_error(error);
+
+            //Empty frames can come without any data at all
+            let data: &[u8] = if data.is_null() || data_size <= 0 {
+                &[]
+            } else {
+                slice::from_raw_parts(data.cast::<u8>(), data_size as usize)
+            };
+
+            return Ok(FrameView {
+                data,
+                format: Rs2Format::from_i32(format.assume_init() as i32).unwrap_or_default(),
+                width: width.max(0) as usize,
+                height: height.max(0) as usize,
+                stride: stride.max(0) as usize,
+                bytes_per_pixel: (bits_per_pixel / BITS_IN_A_BYTE).max(0) as usize,
+            });
+        }
+    }
+
+    pub(crate) fn profile_data(&self) -> Result<ProfileData, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let profile = rs2_get_frame_stream_profile(self.frame, &mut error);
+            check_error(error)?;
+
+            rs2_free_error(error);
+            return ProfileData::new(profile);
+        }
+    }
+
+    //Read from the frame's stream profile on every call, PipelineProfile::intrinsics once per stream is cheaper.
+    //None for frames that are not video or modes without calibration data
+    pub fn intrinsics(&self) -> Result<Option<Intrinsics>, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let profile = rs2_get_frame_stream_profile(self.frame, &mut error);
+            check_error(error)?;
+
+            rs2_free_error(error);
+            return Intrinsics::new(profile);
+        }
+    }
+
+    //Transform from this frame's stream to the other frame's, e.g. depth to color
+    pub fn extrinsics_to(&self, to: &SafeFrame) -> Result<Extrinsics, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let from_profile = rs2_get_frame_stream_profile(self.frame, &mut error);
+            check_error(error)?;
+
+            let to_profile = rs2_get_frame_stream_profile(to.frame, &mut error);
+            check_error(error)?;
+
+            rs2_free_error(error);
+            return Extrinsics::new(from_profile, to_profile);
+        }
+    }
+}
+
+//Another handle on the same frame, nothing gets copied. Frames come from a pool in librealsense,
+//so holding on to clones for long can starve the stream
+impl Clone for SafeFrame {
+    fn clone(&self) -> Self {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            rs2_frame_add_ref(self.frame, &mut error);
+            //Only fails for a null frame, which a SafeFrame never holds
+            if let Err(err) = check_error(error) {
+                panic!("Could not add a reference to the frame: {}", err);
+            }
+
+            rs2_free_error(error);
+            return SafeFrame { frame: self.frame };
+        }
+    }
+}
+
 impl Drop for SafeFrame {
     fn drop(&mut self) {
         unsafe {
@@ -21,12 +203,123 @@ impl Drop for SafeFrame {
     }
 }
 
+//Frames that arrived together, e.g. the depth and color of one wait_for_frames call
+pub struct Frameset {
+    frames: Vec<SafeFrame>,
+    //Same order as frames. Only what depth, color and by_stream need, this runs for every frame
+    //on librealsense's callback thread
+    profiles: Vec<ProfileData>,
+}
+
+impl Frameset {
+    //Splits a composite frame into its parts. A frame that is not composite, which is what a pipeline
+    //streaming a single stream can hand to a callback, becomes a frameset of one
+    pub fn new(frame: SafeFrame) -> Result<Frameset, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let is_composite = rs2_is_frame_extendable_to(
+                frame.frame,
+                rs2_extension_RS2_EXTENSION_COMPOSITE_FRAME,
+                &mut error,
+            );
+            check_error(error)?;
+
+            if is_composite == 0 {
+                rs2_free_error(error);
+                return Ok(Frameset {
+                    profiles: vec![frame.profile_data()?],
+                    frames: vec![frame],
+                });
+            }
+
+            let count = rs2_embedded_frames_count(frame.frame, &mut error);
+            check_error(error)?;
+
+            let mut frames = Vec::with_capacity(count as usize);
+            let mut profiles = Vec::with_capacity(count as usize);
+            for index in 0..count {
+                //Extracted frames hold their own reference, the composite can go once they are all out
+                let extracted = rs2_extract_frame(frame.frame, index, &mut error);
+                check_error(error)?;
+
+                let extracted = SafeFrame { frame: extracted };
+                profiles.push(extracted.profile_data()?);
+                frames.push(extracted);
+            }
+
+            rs2_free_error(error);
+            return Ok(Frameset { frames, profiles });
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.frames.is_empty()
+    }
+
+    //First depth frame, there is only ever one with the usual configs
+    pub fn depth(&self) -> Option<&SafeFrame> {
+        self.find(|profile| profile.stream == Rs2StreamKind::Depth)
+    }
+
+    pub fn color(&self) -> Option<&SafeFrame> {
+        self.find(|profile| profile.stream == Rs2StreamKind::Color)
+    }
+
+    //Stereo cameras number their infrared streams from 1, left is 1 and right is 2
+    pub fn infrared(&self, index: i32) -> Option<&SafeFrame> {
+        self.by_stream(Rs2StreamKind::Infrared, index)
+    }
+
+    pub fn by_stream(&self, stream: Rs2StreamKind, index: i32) -> Option<&SafeFrame> {
+        self.find(|profile| profile.stream == stream && profile.index == index)
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, SafeFrame> {
+        self.frames.iter()
+    }
+
+    //Same order as iter. Read from librealsense on every call, unlike the stream and index lookups
+    pub fn profiles(&self) -> Result<Vec<StreamProfile>, RealsenseError> {
+        self.frames
+            .iter()
+            .map(|frame| frame.stream_profile())
+            .collect()
+    }
+
+    fn find(&self, matches: impl Fn(&ProfileData) -> bool) -> Option<&SafeFrame> {
+        let position = self.profiles.iter().position(matches)?;
+        return Some(&self.frames[position]);
+    }
+}
+
+impl IntoIterator for Frameset {
+    type Item = SafeFrame;
+    type IntoIter = std::vec::IntoIter<SafeFrame>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.frames.into_iter()
+    }
+}
+
+impl<'a> IntoIterator for &'a Frameset {
+    type Item = &'a SafeFrame;
+    type IntoIter = std::slice::Iter<'a, SafeFrame>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.frames.iter()
+    }
+}
+
 #[derive(Default, Debug)]
 pub struct FrameInfo {
     frame_number: u64,
     frame_timestamp: f64,
     frame_timestamp_domain: u32,
-    frame_metadata_time_of_arrival: i64,
     pub format: Rs2Format,
     index: i32,
     unique_id: i32,
@@ -36,6 +329,9 @@ pub struct FrameInfo {
     pub bits_per_pixel: i32,
     pub stride: i32,
     data_size: i32,
+    //Meters per Z16 unit, only set for depth frames
+    pub depth_units: Option<f32>,
+    pub metadata: FrameMetadata,
 }
 
 impl FrameInfo {
@@ -56,33 +352,13 @@ impl FrameInfo {
             let frame_timestamp_domain_str =
                 CStr::from_ptr(rs2_timestamp_domain_to_string(frame_timestamp_domain));
 
-            let frame_metadata_time_of_arrival = rs2_get_frame_metadata(
-                frame.frame,
-                rs2_frame_metadata_value_RS2_FRAME_METADATA_TIME_OF_ARRIVAL,
-                &mut error,
-            );
-            check_error(error)?;
+            let metadata = FrameMetadata::new(frame)?;
 
             let profile = rs2_get_frame_stream_profile(frame.frame, &mut error);
-
-            //This has high potential to be a source of error
-            let mut stream = MaybeUninit::uninit();
-            let mut format = MaybeUninit::uninit();
-            let mut index = MaybeUninit::uninit();
-            let mut unique_id = MaybeUninit::uninit();
-            let mut frame_rate = MaybeUninit::uninit();
-
-            rs2_get_stream_profile_data(
-                profile,
-                stream.as_mut_ptr(),
-                format.as_mut_ptr(),
-                index.as_mut_ptr(),
-                unique_id.as_mut_ptr(),
-                frame_rate.as_mut_ptr(),
-                &mut error,
-            );
             check_error(error)?;
 
+            let profile = ProfileData::new(profile)?;
+
             let width = rs2_get_frame_width(frame.frame, &mut error);
             check_error(error)?;
 
@@ -100,27 +376,85 @@ impl FrameInfo {
 
             debug_assert_eq!(data_size, width * height * bits_per_pixel / BITS_IN_A_BYTE);
 
+            let is_depth = rs2_is_frame_extendable_to(
+                frame.frame,
+                rs2_extension_RS2_EXTENSION_DEPTH_FRAME,
+                &mut error,
+            );
+            check_error(error)?;
+
+            let mut depth_units = None;
+            if is_depth != 0 {
+                depth_units = Some(rs2_depth_frame_get_units(frame.frame, &mut error));
+                check_error(error)?;
+            }
+
             rs2_free_error(error);
 
             return Ok(FrameInfo {
                 frame_number,
                 frame_timestamp,
                 frame_timestamp_domain,
-                frame_metadata_time_of_arrival,
-                format: Rs2Format::from_i32(format.assume_init() as i32).unwrap(),
-                index: index.assume_init(),
-                unique_id: unique_id.assume_init(),
-                frame_rate: frame_rate.assume_init(),
+                format: profile.format,
+                index: profile.index,
+                unique_id: profile.unique_id,
+                frame_rate: profile.frame_rate,
                 width,
                 height,
                 bits_per_pixel,
                 stride,
                 data_size,
+                depth_units,
+                metadata,
             });
         }
     }
 }
 
+//The metadata most people look at, each None when the device does not report it.
+//Anything else can be read with SafeFrame::metadata
+#[derive(Default, Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrameMetadata {
+    pub frame_counter: Option<i64>,
+    //Microseconds, from the device clock when the readout started
+    pub frame_timestamp: Option<i64>,
+    //Microseconds, from the sensor clock at the middle of the exposure
+    pub sensor_timestamp: Option<i64>,
+    //Milliseconds, from the host clock when the frame reached it
+    pub time_of_arrival: Option<i64>,
+    pub backend_timestamp: Option<i64>,
+    //Microseconds
+    pub actual_exposure: Option<i64>,
+    pub gain_level: Option<i64>,
+    //1 while auto exposure is on
+    pub auto_exposure: Option<i64>,
+    pub actual_fps: Option<i64>,
+    pub laser_power: Option<i64>,
+    pub laser_power_mode: Option<i64>,
+    pub emitter_mode: Option<i64>,
+    pub temperature: Option<i64>,
+}
+
+impl FrameMetadata {
+    pub fn new(frame: &SafeFrame) -> Result<FrameMetadata, RealsenseError> {
+        return Ok(FrameMetadata {
+            frame_counter: frame.metadata(Rs2FrameMetadata::FrameCounter)?,
+            frame_timestamp: frame.metadata(Rs2FrameMetadata::FrameTimestamp)?,
+            sensor_timestamp: frame.metadata(Rs2FrameMetadata::SensorTimestamp)?,
+            time_of_arrival: frame.metadata(Rs2FrameMetadata::TimeOfArrival)?,
+            backend_timestamp: frame.metadata(Rs2FrameMetadata::BackendTimestamp)?,
+            actual_exposure: frame.metadata(Rs2FrameMetadata::ActualExposure)?,
+            gain_level: frame.metadata(Rs2FrameMetadata::GainLevel)?,
+            auto_exposure: frame.metadata(Rs2FrameMetadata::AutoExposure)?,
+            actual_fps: frame.metadata(Rs2FrameMetadata::ActualFps)?,
+            laser_power: frame.metadata(Rs2FrameMetadata::FrameLaserPower)?,
+            laser_power_mode: frame.metadata(Rs2FrameMetadata::FrameLaserPowerMode)?,
+            emitter_mode: frame.metadata(Rs2FrameMetadata::FrameEmitterMode)?,
+            temperature: frame.metadata(Rs2FrameMetadata::Temperature)?,
+        });
+    }
+}
+
 #[derive(Debug)]
 pub struct FrameData {
     pub raw_data: Array2<u8>, //this size should be height * stride, where stride is width*bytes per pixel
@@ -129,35 +463,87 @@ pub struct FrameData {
 }
 
 impl FrameData {
+    //Copies the frame out in one go, use SafeFrame::view to read it without copying at all.
+    //bits_per_pixel is no longer needed since the frame knows its own size
     pub fn new(
         frame: &SafeFrame,
         height: usize,
         stride: usize,
-        bits_per_pixel: usize,
+        _bits_per_pixel: usize,
     ) -> Result<FrameData, RealsenseError> {
-        unsafe {
-            let mut error = std::ptr::null_mut::<rs2_error>();
+        let data = frame.view()?.bytes();
 
-            let frame_data = rs2_get_frame_data(frame.frame, &mut error);
+        let mut raw_data = Array2::<u8>::zeros((height, stride));
+        let len = data.len().min(height * stride);
+        raw_data.as_slice_mut().unwrap()[..len].copy_from_slice(&data[..len]);
 
-            check_error(error)?;
+        return Ok(FrameData {
+            raw_data: raw_data,
+            height: height,
+            stride: stride,
+        });
+    }
+}
 
-            let slice = slice::from_raw_parts(frame_data.cast::<u8>(), bits_per_pixel);
-            let mut raw_data = Array2::<u8>::zeros((height, stride));
+//The frame's pixels where librealsense put them, valid for as long as the frame is borrowed.
+//Rows can be padded, stride is the length of a row in bytes and width the number of pixels in it
+#[derive(Debug, Clone, Copy)]
+pub struct FrameView<'a> {
+    data: &'a [u8],
+    pub format: Rs2Format,
+    pub width: usize,
+    pub height: usize,
+    pub stride: usize,
+    pub bytes_per_pixel: usize,
+}
 
-            for row in 0..height {
-                for col in 0..stride {
-                    raw_data[[row, col]] = *slice.get_unchecked(row * stride + col);
-                }
-            }
-            rs2_free_error(error);
-            drop(frame_data); //Do I even need this? Rust should just drop the pointer
-            return Ok(FrameData {
-                raw_data: raw_data,
-                height: height,
-                stride: stride,
-            });
+impl<'a> FrameView<'a> {
+    pub fn bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    //Indexed [[row, byte]], padding included, same layout as FrameData::raw_data
+    pub fn as_array2(&self) -> Option<ArrayView2<'a, u8>> {
+        let data = self.data.get(..self.height * self.stride)?;
+        return ArrayView2::from_shape((self.height, self.stride), data).ok();
+    }
+
+    //Indexed [[row, col, channel]], e.g. the three channels of RGB8. Padding is skipped
+    pub fn as_array3(&self) -> Option<ArrayView3<'a, u8>> {
+        let shape = (self.height, self.width, self.bytes_per_pixel).strides((
+            self.stride,
+            self.bytes_per_pixel,
+            1,
+        ));
+        return ArrayView3::from_shape(shape, self.data).ok();
+    }
+
+    //Every 16 bit value, padding included. Z16, Y16 and the like are stored little endian,
+    //which is what every host librealsense runs on uses too. None for other formats, including
+    //YUYV and UYVY which are 16 bits per pixel but pack two 8 bit values into each
+    pub fn as_u16(&self) -> Option<&'a [u16]> {
+        let is_u16 = matches!(
+            self.format,
+            Rs2Format::Z16 | Rs2Format::Disparity16 | Rs2Format::Y16 | Rs2Format::RAW16
+        );
+        if !is_u16 || cfg!(target_endian = "big") {
+            return None;
+        }
+
+        //librealsense hands out aligned buffers, this only fails if that ever changes
+        let (prefix, data, _) = unsafe { self.data.align_to::<u16>() };
+        if !prefix.is_empty() {
+            return None;
         }
+
+        return Some(data);
+    }
+
+    //Indexed [[row, col]], e.g. raw Z16 depth. Padding is skipped
+    pub fn as_u16_array2(&self) -> Option<ArrayView2<'a, u16>> {
+        let data = self.as_u16()?;
+        let shape = (self.height, self.width).strides((self.stride / 2, 1));
+        return ArrayView2::from_shape(shape, data).ok();
     }
 }
 
@@ -0,0 +1,96 @@
+use crate::{Frameset, PipelineConfig, RealsenseError, RealsenseInstance};
+use futures_core::Stream;
+use std::{
+    collections::VecDeque,
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll, Waker},
+};
+
+//Filled by the frame callback on a librealsense thread, drained by poll_next
+struct Shared {
+    queue: VecDeque<Result<Frameset, RealsenseError>>,
+    capacity: usize,
+    waker: Option<Waker>,
+}
+
+//Framesets from the pipeline's callback as a Stream, for async runtimes like tokio. Never ends on its own,
+//dropping it stops the pipeline and closes the device
+pub struct FrameStream {
+    instance: RealsenseInstance,
+    shared: Arc<Mutex<Shared>>,
+}
+
+impl FrameStream {
+    //Buffers at most capacity framesets. When the consumer falls behind the oldest ones are dropped,
+    //so whatever it polls next is as fresh as possible
+    pub fn new(
+        mut instance: RealsenseInstance,
+        config: &PipelineConfig,
+        capacity: usize,
+    ) -> Result<Self, RealsenseError> {
+        let shared = Arc::new(Mutex::new(Shared {
+            queue: VecDeque::with_capacity(capacity),
+            capacity: capacity.max(1),
+            waker: None,
+        }));
+
+        let callback_shared = Arc::clone(&shared);
+        instance.start_with_fallible_callback(config, move |frames| {
+            let mut shared = callback_shared.lock().unwrap();
+
+            if shared.queue.len() >= shared.capacity {
+                log::trace!("Frame stream is full, dropping the oldest frameset");
+                shared.queue.pop_front();
+            }
+            shared.queue.push_back(frames);
+
+            if let Some(waker) = shared.waker.take() {
+                waker.wake();
+            }
+        })?;
+
+        return Ok(FrameStream { instance, shared });
+    }
+
+    pub fn instance(&self) -> &RealsenseInstance {
+        &self.instance
+    }
+
+    //Stops the pipeline and hands the instance back, anything still buffered is dropped
+    pub fn into_inner(mut self) -> RealsenseInstance {
+        self.instance.stop();
+        return self.instance;
+    }
+}
+
+impl Stream for FrameStream {
+    //Err for framesets librealsense delivered but that could not be read
+    type Item = Result<Frameset, RealsenseError>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let mut shared = self.shared.lock().unwrap();
+
+        if let Some(frames) = shared.queue.pop_front() {
+            return Poll::Ready(Some(frames));
+        }
+
+        //Only the most recent poll's waker has to be woken
+        match &shared.waker {
+            Some(waker) if waker.will_wake(cx.waker()) => {}
+            _ => shared.waker = Some(cx.waker().clone()),
+        }
+
+        return Poll::Pending;
+    }
+}
+
+impl RealsenseInstance {
+    pub fn frame_stream(
+        self,
+        config: &PipelineConfig,
+        capacity: usize,
+    ) -> Result<FrameStream, RealsenseError> {
+        return FrameStream::new(self, config, capacity);
+    }
+}
@@ -51,6 +51,10 @@ impl ImageData {
         }
     }
 
+    pub fn frame_info(&self) -> &FrameInfo {
+        &self.frame_info
+    }
+
     pub fn get_better_raw_pixel(&self, row: usize, col: usize) -> BetterRawPixel {
         match self.frame_info.format {
             Rs2Format::RGB8 => {
@@ -163,6 +167,58 @@ impl ImageData {
         return result;
     }
 
+    //Raw Z16 value at column x and row y, stored little endian two bytes per pixel
+    pub fn depth_at(&self, x: usize, y: usize) -> Option<u16> {
+        if self.frame_info.format != Rs2Format::Z16
+            || x >= self.frame_info.width as usize
+            || y >= self.frame_info.height as usize
+        {
+            return None;
+        }
+
+        let low_byte = self.frame_data.raw_data[[y, x * 2]] as u16;
+        let high_byte = (self.frame_data.raw_data[[y, x * 2 + 1]] as u16) << BITS_IN_A_BYTE;
+        return Some(high_byte | low_byte);
+    }
+
+    //Every raw Z16 value, row major, e.g. for ColorToDepth. None for frames that are not depth
+    pub fn depth_data(&self) -> Option<Vec<u16>> {
+        let width = self.frame_info.width as usize;
+        let height = self.frame_info.height as usize;
+
+        let mut data = Vec::with_capacity(width * height);
+        for y in 0..height {
+            for x in 0..width {
+                data.push(self.depth_at(x, y)?);
+            }
+        }
+
+        return Some(data);
+    }
+
+    //Same as rs2_depth_frame_get_distance, 0.0 where the camera has no depth.
+    //None for frames that are not depth or pixels outside of the frame
+    pub fn distance_at(&self, x: usize, y: usize) -> Option<f32> {
+        let depth_units = self.frame_info.depth_units?;
+        return Some(self.depth_at(x, y)? as f32 * depth_units);
+    }
+
+    //Every pixel in meters, indexed [[row, col]]. None for frames that are not depth
+    pub fn depth_meters(&self) -> Option<Array2<f32>> {
+        let depth_units = self.frame_info.depth_units?;
+        if self.frame_info.format != Rs2Format::Z16 {
+            return None;
+        }
+
+        let shape = (
+            self.frame_info.height as usize,
+            self.frame_info.width as usize,
+        );
+        return Some(Array2::from_shape_fn(shape, |(row, col)| {
+            self.depth_at(col, row).unwrap_or(0) as f32 * depth_units
+        }));
+    }
+
     pub fn to_image(&self) -> Option<DynamicImage> {
         match self.frame_info.format {
             Rs2Format::RGB8 => return Some(image::DynamicImage::ImageRgb8(self.to_rgb_image())),
@@ -2,15 +2,41 @@
 #![allow(non_camel_case_types)]
 #![allow(non_snake_case)]
 mod bindings;
+mod context;
+mod depth_sensor;
+mod device;
 mod frame;
+#[cfg(feature = "async")]
+mod frame_stream;
 mod image_data;
+mod pipeline;
+mod preset;
+mod projection;
 mod realsense_init;
+mod roi;
+mod sensor;
+mod snapshot;
+mod stream_profile;
+mod supervisor;
 mod types;
 mod utils;
 
 pub use bindings::*;
+pub use context::*;
+pub use depth_sensor::*;
+pub use device::*;
 pub use frame::*;
+#[cfg(feature = "async")]
+pub use frame_stream::*;
 pub use image_data::*;
+pub use pipeline::*;
+pub use preset::*;
+pub use projection::*;
 pub use realsense_init::*;
+pub use roi::*;
+pub use sensor::*;
+pub use snapshot::*;
+pub use stream_profile::*;
+pub use supervisor::*;
 pub use types::*;
 pub use utils::*;
@@ -0,0 +1,256 @@
+use crate::{
+    bindings::*, check_error, format::Rs2Format, profiles_in_list, stream::Rs2StreamKind,
+    Extrinsics, Intrinsics, MotionIntrinsics, ProfileData, RealsenseError, StreamProfile,
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct StreamRequest {
+    pub stream: Rs2StreamKind,
+    pub index: i32,
+    //0 for width, height or fps and Rs2Format::Any leave the choice to librealsense
+    pub width: i32,
+    pub height: i32,
+    pub format: Rs2Format,
+    pub fps: i32,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum ConfigStep {
+    EnableStream(StreamRequest),
+    //None disables every index of the stream
+    DisableStream(Rs2StreamKind, Option<i32>),
+    EnableAllStreams,
+    DisableAllStreams,
+}
+
+//Plain description of what the pipeline should stream. Nothing touches librealsense until it is
+//handed to RealsenseInstance::start, where the steps are replayed in order onto a fresh rs2_config
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct PipelineConfig {
+    steps: Vec<ConfigStep>,
+}
+
+impl PipelineConfig {
+    pub fn new() -> Self {
+        PipelineConfig::default()
+    }
+
+    pub fn enable_stream(
+        mut self,
+        stream: Rs2StreamKind,
+        index: i32,
+        width: i32,
+        height: i32,
+        format: Rs2Format,
+        fps: i32,
+    ) -> Self {
+        self.steps.push(ConfigStep::EnableStream(StreamRequest {
+            stream,
+            index,
+            width,
+            height,
+            format,
+            fps,
+        }));
+        self
+    }
+
+    //Handy with the results of Device::stream_profiles and StreamProfileQuery::closest
+    pub fn enable_profile(self, profile: &StreamProfile) -> Self {
+        self.enable_stream(
+            profile.stream,
+            profile.index,
+            profile.width,
+            profile.height,
+            profile.format,
+            profile.frame_rate,
+        )
+    }
+
+    pub fn disable_stream(mut self, stream: Rs2StreamKind) -> Self {
+        self.steps.push(ConfigStep::DisableStream(stream, None));
+        self
+    }
+
+    pub fn disable_indexed_stream(mut self, stream: Rs2StreamKind, index: i32) -> Self {
+        self.steps
+            .push(ConfigStep::DisableStream(stream, Some(index)));
+        self
+    }
+
+    pub fn enable_all_streams(mut self) -> Self {
+        self.steps.push(ConfigStep::EnableAllStreams);
+        self
+    }
+
+    pub fn disable_all_streams(mut self) -> Self {
+        self.steps.push(ConfigStep::DisableAllStreams);
+        self
+    }
+
+    //Every stream explicitly requested so far, in order
+    pub fn requested_streams(&self) -> Vec<StreamRequest> {
+        self.steps
+            .iter()
+            .filter_map(|step| match step {
+                ConfigStep::EnableStream(request) => Some(*request),
+                _ => None,
+            })
+            .collect()
+    }
+
+    pub(crate) unsafe fn apply(&self, config: *mut rs2_config) -> Result<(), RealsenseError> {
+        let mut error = std::ptr::null_mut::<rs2_error>();
+
+        for step in &self.steps {
+            match *step {
+                ConfigStep::EnableStream(request) => rs2_config_enable_stream(
+                    config,
+                    request.stream as u32,
+                    request.index,
+                    request.width,
+                    request.height,
+                    request.format as u32,
+                    request.fps,
+                    &mut error,
+                ),
+                ConfigStep::DisableStream(stream, None) => {
+                    rs2_config_disable_stream(config, stream as u32, &mut error)
+                }
+                ConfigStep::DisableStream(stream, Some(index)) => {
+                    rs2_config_disable_indexed_stream(config, stream as u32, index, &mut error)
+                }
+                ConfigStep::EnableAllStreams => rs2_config_enable_all_stream(config, &mut error),
+                ConfigStep::DisableAllStreams => rs2_config_disable_all_streams(config, &mut error),
+            }
+            check_error(error)?;
+        }
+
+        rs2_free_error(error);
+        return Ok(());
+    }
+}
+
+//What the pipeline actually resolved and started with
+pub struct PipelineProfile {
+    pub profile: *mut rs2_pipeline_profile,
+}
+
+unsafe impl Sync for PipelineProfile {}
+
+unsafe impl Send for PipelineProfile {}
+
+impl PipelineProfile {
+    //One entry per stream the pipeline negotiated, with Any formats and zero sizes filled in
+    pub fn streams(&self) -> Result<Vec<StreamProfile>, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let list = rs2_pipeline_profile_get_streams(self.profile, &mut error);
+            check_error(error)?;
+
+            let streams = profiles_in_list(list);
+            rs2_delete_stream_profiles_list(list);
+
+            rs2_free_error(error);
+            return streams;
+        }
+    }
+
+    //Transform between two of the negotiated streams, e.g. depth to color. Profiles are matched by unique_id
+    pub fn extrinsics(
+        &self,
+        from: &StreamProfile,
+        to: &StreamProfile,
+    ) -> Result<Extrinsics, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let list = rs2_pipeline_profile_get_streams(self.profile, &mut error);
+            check_error(error)?;
+
+            let extrinsics = find_profile(list, from).and_then(|from| {
+                let to = find_profile(list, to)?;
+                return Extrinsics::new(from, to);
+            });
+            rs2_delete_stream_profiles_list(list);
+
+            rs2_free_error(error);
+            return extrinsics;
+        }
+    }
+}
+
+//Calibration of the negotiated streams
+impl PipelineProfile {
+    //None for streams that are not video or modes without calibration data
+    pub fn intrinsics(&self, stream: &StreamProfile) -> Result<Option<Intrinsics>, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let list = rs2_pipeline_profile_get_streams(self.profile, &mut error);
+            check_error(error)?;
+
+            let intrinsics =
+                find_profile(list, stream).and_then(|profile| Intrinsics::new(profile));
+            rs2_delete_stream_profiles_list(list);
+
+            rs2_free_error(error);
+            return intrinsics;
+        }
+    }
+
+    //None for streams that are not gyro or accel, or when the motion module is not calibrated
+    pub fn motion_intrinsics(
+        &self,
+        stream: &StreamProfile,
+    ) -> Result<Option<MotionIntrinsics>, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let list = rs2_pipeline_profile_get_streams(self.profile, &mut error);
+            check_error(error)?;
+
+            let intrinsics =
+                find_profile(list, stream).map(|profile| MotionIntrinsics::new(profile));
+            rs2_delete_stream_profiles_list(list);
+
+            rs2_free_error(error);
+            return intrinsics;
+        }
+    }
+}
+
+unsafe fn find_profile(
+    list: *const rs2_stream_profile_list,
+    wanted: &StreamProfile,
+) -> Result<*const rs2_stream_profile, RealsenseError> {
+    let mut error = std::ptr::null_mut::<rs2_error>();
+
+    let count = rs2_get_stream_profiles_count(list, &mut error);
+    check_error(error)?;
+
+    for i in 0..count {
+        let profile = rs2_get_stream_profile(list, i, &mut error);
+        check_error(error)?;
+
+        if ProfileData::new(profile)?.unique_id == wanted.unique_id {
+            rs2_free_error(error);
+            return Ok(profile);
+        }
+    }
+
+    rs2_free_error(error);
+    return Err(RealsenseError::StreamNotFound {
+        stream: wanted.stream,
+        index: wanted.index,
+    });
+}
+
+impl Drop for PipelineProfile {
+    fn drop(&mut self) {
+        unsafe {
+            rs2_delete_pipeline_profile(self.profile);
+        }
+    }
+}
@@ -0,0 +1,444 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+//Advanced mode JSON as exported by rs2_serialize_json or the Intel Viewer. The keys that get
+//tuned most on D400 cameras are typed, everything else is carried through untouched in other
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct AdvancedModePreset {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device: Option<PresetDevice>,
+    #[serde(default)]
+    pub parameters: PresetParameters,
+    #[serde(
+        rename = "schema version",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub schema_version: Option<u32>,
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PresetDevice {
+    #[serde(
+        rename = "fw version",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub firmware_version: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(
+        rename = "product line",
+        default,
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub product_line: Option<String>,
+}
+
+//librealsense writes every value as a string, "150", "True", "on" and so on
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct PresetParameters {
+    #[serde(rename = "controls-autoexposure-auto", default, with = "true_false")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub auto_exposure: Option<bool>,
+    #[serde(rename = "controls-autoexposure-manual", default, with = "number")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub exposure: Option<f32>,
+    #[serde(rename = "controls-depth-gain", default, with = "number")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gain: Option<f32>,
+    #[serde(rename = "controls-laserpower", default, with = "number")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub laser_power: Option<f32>,
+    #[serde(rename = "controls-laserstate", default, with = "on_off")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub laser_state: Option<bool>,
+    #[serde(rename = "param-depthunits", default, with = "number")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth_units: Option<i32>,
+    #[serde(rename = "param-depthclampmin", default, with = "number")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth_clamp_min: Option<i32>,
+    #[serde(rename = "param-depthclampmax", default, with = "number")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub depth_clamp_max: Option<i32>,
+    #[serde(rename = "param-disparityshift", default, with = "number")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub disparity_shift: Option<i32>,
+    #[serde(rename = "param-medianthreshold", default, with = "number")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub median_threshold: Option<i32>,
+    #[serde(rename = "param-leftrightthreshold", default, with = "number")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub left_right_threshold: Option<i32>,
+    #[serde(rename = "param-secondpeakdelta", default, with = "number")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub second_peak_delta: Option<i32>,
+    #[serde(rename = "param-texturecountthresh", default, with = "number")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub texture_count_threshold: Option<i32>,
+    #[serde(rename = "param-texturedifferencethresh", default, with = "number")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub texture_difference_threshold: Option<i32>,
+    #[serde(rename = "param-minscorethresha", default, with = "number")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub min_score_threshold_a: Option<i32>,
+    #[serde(rename = "param-maxscorethreshb", default, with = "number")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_score_threshold_b: Option<i32>,
+    #[serde(rename = "stream-width", default, with = "number")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_width: Option<i32>,
+    #[serde(rename = "stream-height", default, with = "number")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_height: Option<i32>,
+    #[serde(rename = "stream-fps", default, with = "number")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_fps: Option<i32>,
+    #[serde(rename = "stream-depth-format", default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stream_depth_format: Option<String>,
+    #[serde(flatten)]
+    pub other: BTreeMap<String, Value>,
+}
+
+impl AdvancedModePreset {
+    //Older presets keep every parameter at the top level instead of under "parameters", both are accepted
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        let mut value: Value = serde_json::from_str(json)?;
+
+        if let Value::Object(map) = &mut value {
+            if !map.contains_key("parameters") {
+                let (parameters, rest): (serde_json::Map<String, Value>, _) =
+                    std::mem::take(map).into_iter().partition(|(key, _)| {
+                        key != "device" && key != "schema version" && key != "viewer"
+                    });
+                *map = rest;
+                map.insert("parameters".to_string(), Value::Object(parameters));
+            }
+        }
+
+        serde_json::from_value(value)
+    }
+
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    //Sanity checks on the typed keys, one message per problem. These are the limits of the
+    //D400 family and are not a replacement for the checks the firmware does on upload
+    pub fn validate(&self) -> Result<(), Vec<String>> {
+        let parameters = &self.parameters;
+        let mut problems = Vec::new();
+
+        if let Some(laser_power) = parameters.laser_power {
+            if !(0.0..=360.0).contains(&laser_power) {
+                problems.push(format!(
+                    "laser power {} is outside of 0 to 360",
+                    laser_power
+                ));
+            }
+        }
+
+        if let Some(gain) = parameters.gain {
+            if !(16.0..=248.0).contains(&gain) {
+                problems.push(format!("gain {} is outside of 16 to 248", gain));
+            }
+        }
+
+        if let Some(exposure) = parameters.exposure {
+            if exposure <= 0.0 {
+                problems.push(format!("exposure {} has to be positive", exposure));
+            }
+        }
+
+        if let Some(depth_units) = parameters.depth_units {
+            if depth_units <= 0 {
+                problems.push(format!("depth units {} have to be positive", depth_units));
+            }
+        }
+
+        for (name, clamp) in [
+            ("depth clamp min", parameters.depth_clamp_min),
+            ("depth clamp max", parameters.depth_clamp_max),
+        ] {
+            if let Some(clamp) = clamp {
+                if !(0..=65536).contains(&clamp) {
+                    problems.push(format!("{} {} is outside of 0 to 65536", name, clamp));
+                }
+            }
+        }
+
+        if let (Some(min), Some(max)) = (parameters.depth_clamp_min, parameters.depth_clamp_max) {
+            if min > max {
+                problems.push(format!(
+                    "depth clamp min {} is larger than depth clamp max {}",
+                    min, max
+                ));
+            }
+        }
+
+        for (name, size) in [
+            ("stream width", parameters.stream_width),
+            ("stream height", parameters.stream_height),
+            ("stream fps", parameters.stream_fps),
+        ] {
+            if let Some(size) = size {
+                if size <= 0 {
+                    problems.push(format!("{} {} has to be positive", name, size));
+                }
+            }
+        }
+
+        if problems.is_empty() {
+            return Ok(());
+        }
+        return Err(problems);
+    }
+}
+
+//Values written as strings, but plain JSON numbers are accepted too
+mod number {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use serde_json::Value;
+    use std::{fmt::Display, str::FromStr};
+
+    pub fn serialize<S, T>(value: &Option<T>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+        T: Display,
+    {
+        match value {
+            Some(value) => serializer.serialize_str(&value.to_string()),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D, T>(deserializer: D) -> Result<Option<T>, D::Error>
+    where
+        D: Deserializer<'de>,
+        T: FromStr,
+        T::Err: Display,
+    {
+        match Option::<Value>::deserialize(deserializer)? {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::String(text)) => text.trim().parse().map(Some).map_err(D::Error::custom),
+            Some(Value::Number(number)) => number
+                .to_string()
+                .parse()
+                .map(Some)
+                .map_err(D::Error::custom),
+            Some(other) => Err(D::Error::custom(format!(
+                "expected a number, got {}",
+                other
+            ))),
+        }
+    }
+}
+
+//"True" and "False"
+mod true_false {
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<bool>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::flag::serialize(value, serializer, "True", "False")
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<bool>, D::Error> {
+        super::flag::deserialize(deserializer)
+    }
+}
+
+//"on" and "off"
+mod on_off {
+    use serde::{Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<bool>,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        super::flag::serialize(value, serializer, "on", "off")
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<bool>, D::Error> {
+        super::flag::deserialize(deserializer)
+    }
+}
+
+mod flag {
+    use serde::{de::Error, Deserialize, Deserializer, Serializer};
+    use serde_json::Value;
+
+    pub fn serialize<S: Serializer>(
+        value: &Option<bool>,
+        serializer: S,
+        on: &str,
+        off: &str,
+    ) -> Result<S::Ok, S::Error> {
+        match value {
+            Some(true) => serializer.serialize_str(on),
+            Some(false) => serializer.serialize_str(off),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    //Whichever spelling comes in, both flag styles and plain JSON booleans are understood
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Option<bool>, D::Error> {
+        match Option::<Value>::deserialize(deserializer)? {
+            None | Some(Value::Null) => Ok(None),
+            Some(Value::Bool(flag)) => Ok(Some(flag)),
+            Some(Value::String(text)) => match text.trim().to_lowercase().as_str() {
+                "true" | "on" | "1" => Ok(Some(true)),
+                "false" | "off" | "0" => Ok(Some(false)),
+                _ => Err(D::Error::custom(format!("expected a flag, got {}", text))),
+            },
+            Some(other) => Err(D::Error::custom(format!("expected a flag, got {}", other))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //Trimmed down export from a D435, values written as strings the way librealsense does
+    const NESTED: &str = r#"{
+        "device": {
+            "fw version": "05.13.00.50",
+            "name": "Intel RealSense D435",
+            "product line": "D400"
+        },
+        "parameters": {
+            "controls-autoexposure-auto": "True",
+            "controls-autoexposure-manual": "8500",
+            "controls-depth-gain": "16",
+            "controls-laserpower": "150",
+            "controls-laserstate": "on",
+            "param-depthclampmax": "65536",
+            "param-depthclampmin": "0",
+            "param-depthunits": "1000",
+            "param-disparityshift": "0",
+            "stream-depth-format": "Z16",
+            "stream-fps": "30",
+            "stream-height": "480",
+            "stream-width": "848",
+            "param-censususize": "9"
+        },
+        "schema version": 1,
+        "viewer": { "stream-ir-state": "1" }
+    }"#;
+
+    //Same preset the way older firmware and the Viewer wrote it
+    const FLAT: &str = r#"{
+        "controls-autoexposure-auto": "True",
+        "controls-autoexposure-manual": "8500",
+        "controls-depth-gain": "16",
+        "controls-laserpower": "150",
+        "controls-laserstate": "on",
+        "param-depthclampmax": "65536",
+        "param-depthclampmin": "0",
+        "param-depthunits": "1000",
+        "param-disparityshift": "0",
+        "stream-depth-format": "Z16",
+        "stream-fps": "30",
+        "stream-height": "480",
+        "stream-width": "848",
+        "param-censususize": "9"
+    }"#;
+
+    #[test]
+    fn nested_layout() {
+        let preset = AdvancedModePreset::from_json(NESTED).unwrap();
+        let parameters = &preset.parameters;
+
+        assert_eq!(preset.schema_version, Some(1));
+        assert_eq!(
+            preset.device.as_ref().unwrap().firmware_version.as_deref(),
+            Some("05.13.00.50")
+        );
+        assert_eq!(parameters.auto_exposure, Some(true));
+        assert_eq!(parameters.exposure, Some(8500.0));
+        assert_eq!(parameters.laser_power, Some(150.0));
+        assert_eq!(parameters.laser_state, Some(true));
+        assert_eq!(parameters.depth_units, Some(1000));
+        assert_eq!(parameters.stream_width, Some(848));
+        assert_eq!(parameters.stream_depth_format.as_deref(), Some("Z16"));
+        assert!(parameters.other.contains_key("param-censususize"));
+        assert!(preset.other.contains_key("viewer"));
+        assert_eq!(preset.validate(), Ok(()));
+    }
+
+    #[test]
+    fn flat_layout_matches_nested() {
+        let flat = AdvancedModePreset::from_json(FLAT).unwrap();
+        let nested = AdvancedModePreset::from_json(NESTED).unwrap();
+
+        assert_eq!(flat.device, None);
+        assert_eq!(flat.parameters, nested.parameters);
+    }
+
+    #[test]
+    fn round_trip_keeps_string_encoding() {
+        let preset = AdvancedModePreset::from_json(NESTED).unwrap();
+        let json = preset.to_json().unwrap();
+
+        let value: Value = serde_json::from_str(&json).unwrap();
+        assert_eq!(value["parameters"]["controls-autoexposure-auto"], "True");
+        assert_eq!(value["parameters"]["controls-laserstate"], "on");
+        assert_eq!(value["parameters"]["param-depthunits"], "1000");
+        assert_eq!(AdvancedModePreset::from_json(&json).unwrap(), preset);
+    }
+
+    #[test]
+    fn plain_json_values_are_accepted() {
+        let preset = AdvancedModePreset::from_json(
+            r#"{ "parameters": {
+                "controls-autoexposure-auto": false,
+                "controls-laserstate": "OFF",
+                "controls-laserpower": 90.5,
+                "param-depthunits": " 100 "
+            } }"#,
+        )
+        .unwrap();
+
+        assert_eq!(preset.parameters.auto_exposure, Some(false));
+        assert_eq!(preset.parameters.laser_state, Some(false));
+        assert_eq!(preset.parameters.laser_power, Some(90.5));
+        assert_eq!(preset.parameters.depth_units, Some(100));
+    }
+
+    #[test]
+    fn malformed_values_are_rejected() {
+        assert!(AdvancedModePreset::from_json(
+            r#"{ "parameters": { "controls-laserpower": "lots" } }"#
+        )
+        .is_err());
+        assert!(AdvancedModePreset::from_json(
+            r#"{ "parameters": { "controls-laserstate": "maybe" } }"#
+        )
+        .is_err());
+    }
+
+    #[test]
+    fn out_of_range_values_fail_validation() {
+        let mut preset = AdvancedModePreset::from_json(NESTED).unwrap();
+        preset.parameters.laser_power = Some(400.0);
+        preset.parameters.depth_clamp_min = Some(70000);
+
+        let problems = preset.validate().unwrap_err();
+        assert_eq!(problems.len(), 3);
+        assert!(problems[0].contains("laser power 400"));
+    }
+}
@@ -0,0 +1,471 @@
+use crate::{bindings::*, distortion::Distortion, Extrinsics, Intrinsics};
+
+//Rust versions of librealsense's rsutil.h, kept to the same math so results match the C library.
+//They need neither FFI nor a camera, so they work on recorded data and inline into per pixel loops
+
+//Point in meters in the stream's 3D coordinates to a pixel in its image
+#[inline]
+pub fn project_point_to_pixel(intrinsics: &Intrinsics, point: [f32; 3]) -> [f32; 2] {
+    let coeffs = &intrinsics.coeffs;
+    let mut x = point[0] / point[2];
+    let mut y = point[1] / point[2];
+
+    match intrinsics.model {
+        Distortion::ModifiedBrownConrady | Distortion::InverseBrownConrady => {
+            let r2 = x * x + y * y;
+            let f = 1.0 + coeffs[0] * r2 + coeffs[1] * r2 * r2 + coeffs[4] * r2 * r2 * r2;
+            x *= f;
+            y *= f;
+            let dx = x + 2.0 * coeffs[2] * x * y + coeffs[3] * (r2 + 2.0 * x * x);
+            let dy = y + 2.0 * coeffs[3] * x * y + coeffs[2] * (r2 + 2.0 * y * y);
+            x = dx;
+            y = dy;
+        }
+        Distortion::BrownConrady => {
+            let r2 = x * x + y * y;
+            let f = 1.0 + coeffs[0] * r2 + coeffs[1] * r2 * r2 + coeffs[4] * r2 * r2 * r2;
+            let dx = x * f + 2.0 * coeffs[2] * x * y + coeffs[3] * (r2 + 2.0 * x * x);
+            let dy = y * f + 2.0 * coeffs[3] * x * y + coeffs[2] * (r2 + 2.0 * y * y);
+            x = dx;
+            y = dy;
+        }
+        Distortion::FTheta => {
+            let r = (x * x + y * y).sqrt().max(f32::EPSILON);
+            let rd = (1.0 / coeffs[0] as f64
+                * (2.0 * r as f64 * (coeffs[0] as f64 / 2.0).tan()).atan())
+                as f32;
+            x *= rd / r;
+            y *= rd / r;
+        }
+        Distortion::KannalaBrandt4 => {
+            let r = (x * x + y * y).sqrt().max(f32::EPSILON);
+            let theta = (r as f64).atan() as f32;
+            let theta2 = theta * theta;
+            let series = 1.0
+                + theta2
+                    * (coeffs[0]
+                        + theta2 * (coeffs[1] + theta2 * (coeffs[2] + theta2 * coeffs[3])));
+            let rd = theta * series;
+            x *= rd / r;
+            y *= rd / r;
+        }
+        Distortion::None => {}
+    }
+
+    return [
+        x * intrinsics.fx + intrinsics.ppx,
+        y * intrinsics.fy + intrinsics.ppy,
+    ];
+}
+
+//Pixel plus its depth in meters to a point in the stream's 3D coordinates. Modified Brown-Conrady
+//images are distorted forward and can not be deprojected, like librealsense they are treated as undistorted
+#[inline]
+pub fn deproject_pixel_to_point(intrinsics: &Intrinsics, pixel: [f32; 2], depth: f32) -> [f32; 3] {
+    let coeffs = &intrinsics.coeffs;
+    let mut x = (pixel[0] - intrinsics.ppx) / intrinsics.fx;
+    let mut y = (pixel[1] - intrinsics.ppy) / intrinsics.fy;
+    let xo = x;
+    let yo = y;
+
+    match intrinsics.model {
+        //No closed form, 10 iterations is what librealsense settled on
+        Distortion::InverseBrownConrady => {
+            for _ in 0..10 {
+                let r2 = x * x + y * y;
+                let icdist = 1.0 / (1.0 + ((coeffs[4] * r2 + coeffs[1]) * r2 + coeffs[0]) * r2);
+                let xq = x / icdist;
+                let yq = y / icdist;
+                let delta_x = 2.0 * coeffs[2] * xq * yq + coeffs[3] * (r2 + 2.0 * xq * xq);
+                let delta_y = 2.0 * coeffs[3] * xq * yq + coeffs[2] * (r2 + 2.0 * yq * yq);
+                x = (xo - delta_x) * icdist;
+                y = (yo - delta_y) * icdist;
+            }
+        }
+        Distortion::BrownConrady => {
+            for _ in 0..10 {
+                let r2 = x * x + y * y;
+                let icdist = 1.0 / (1.0 + ((coeffs[4] * r2 + coeffs[1]) * r2 + coeffs[0]) * r2);
+                let delta_x = 2.0 * coeffs[2] * x * y + coeffs[3] * (r2 + 2.0 * x * x);
+                let delta_y = 2.0 * coeffs[3] * x * y + coeffs[2] * (r2 + 2.0 * y * y);
+                x = (xo - delta_x) * icdist;
+                y = (yo - delta_y) * icdist;
+            }
+        }
+        //Newton's method on the projection polynomial
+        Distortion::KannalaBrandt4 => {
+            let rd = (x * x + y * y).sqrt().max(f32::EPSILON);
+
+            let mut theta = rd;
+            let mut theta2 = rd * rd;
+            for _ in 0..4 {
+                let f = theta
+                    * (1.0
+                        + theta2
+                            * (coeffs[0]
+                                + theta2
+                                    * (coeffs[1] + theta2 * (coeffs[2] + theta2 * coeffs[3]))))
+                    - rd;
+                if f.abs() < f32::EPSILON {
+                    break;
+                }
+
+                let df = 1.0
+                    + theta2
+                        * (3.0 * coeffs[0]
+                            + theta2
+                                * (5.0 * coeffs[1]
+                                    + theta2 * (7.0 * coeffs[2] + 9.0 * theta2 * coeffs[3])));
+                theta -= f / df;
+                theta2 = theta * theta;
+            }
+
+            let r = (theta as f64).tan() as f32;
+            x *= r / rd;
+            y *= r / rd;
+        }
+        //Same formula as librealsense, which is not the exact inverse of its F-Theta projection
+        Distortion::FTheta => {
+            let rd = (x * x + y * y).sqrt().max(f32::EPSILON);
+            let r = ((coeffs[0] as f64 * rd as f64).tan()
+                / (2.0 * (coeffs[0] as f64 / 2.0).tan()).atan()) as f32;
+            x *= r / rd;
+            y *= r / rd;
+        }
+        Distortion::None | Distortion::ModifiedBrownConrady => {}
+    }
+
+    return [depth * x, depth * y, depth];
+}
+
+//Point in one stream's 3D coordinates to another's, e.g. depth to color
+#[inline]
+pub fn transform_point_to_point(extrinsics: &Extrinsics, point: [f32; 3]) -> [f32; 3] {
+    let rotation = &extrinsics.rotation;
+    let translation = &extrinsics.translation;
+
+    return [
+        rotation[0] * point[0] + rotation[3] * point[1] + rotation[6] * point[2] + translation[0],
+        rotation[1] * point[0] + rotation[4] * point[1] + rotation[7] * point[2] + translation[1],
+        rotation[2] * point[0] + rotation[5] * point[1] + rotation[8] * point[2] + translation[2],
+    ];
+}
+
+//Everything needed to find the depth pixel behind a color pixel without aligning whole frames
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ColorToDepth {
+    //Meters per unit of the depth data, see DepthSensor::depth_scale
+    pub depth_scale: f32,
+    //Range in meters to search along the color pixel's line of sight
+    pub depth_min: f32,
+    pub depth_max: f32,
+    pub depth_intrinsics: Intrinsics,
+    pub color_intrinsics: Intrinsics,
+    pub color_to_depth: Extrinsics,
+    pub depth_to_color: Extrinsics,
+}
+
+impl ColorToDepth {
+    //Same search as rs2_project_color_pixel_to_depth_pixel: walks the depth pixels the color pixel's ray
+    //crosses between depth_min and depth_max and keeps the one that projects back closest to it.
+    //depth_data is row major, depth_intrinsics.width values per row. None when no pixel on the way has depth
+    pub fn color_pixel_to_depth_pixel(
+        &self,
+        depth_data: &[u16],
+        pixel: [f32; 2],
+    ) -> Option<[f32; 2]> {
+        let width = self.depth_intrinsics.width;
+        let height = self.depth_intrinsics.height;
+
+        let start = self.depth_pixel_at(pixel, self.depth_min);
+        let end = self.depth_pixel_at(pixel, self.depth_max);
+
+        let mut closest = None;
+        let mut min_distance = f32::MAX;
+
+        let mut current = start;
+        while is_pixel_in_line(current, start, end) {
+            let (x, y) = (current[0] as i32, current[1] as i32);
+
+            //The line is clamped to width and height inclusive, which can land one past the data
+            let raw = if x < width && y < height {
+                depth_data.get((y * width + x) as usize).copied()
+            } else {
+                None
+            };
+
+            if let Some(raw) = raw.filter(|raw| *raw != 0) {
+                let depth = self.depth_scale * raw as f32;
+                let point = deproject_pixel_to_point(&self.depth_intrinsics, current, depth);
+                let point = transform_point_to_point(&self.depth_to_color, point);
+                let projected = project_point_to_pixel(&self.color_intrinsics, point);
+
+                let distance =
+                    (projected[0] - pixel[0]).powi(2) + (projected[1] - pixel[1]).powi(2);
+                if distance < min_distance {
+                    min_distance = distance;
+                    closest = Some(current);
+                }
+            }
+
+            current = next_pixel_in_line(current, start, end);
+        }
+
+        return closest;
+    }
+
+    //The same search done by librealsense itself
+    pub fn rs2_color_pixel_to_depth_pixel(
+        &self,
+        depth_data: &[u16],
+        pixel: [f32; 2],
+    ) -> Option<[f32; 2]> {
+        let size = (self.depth_intrinsics.width * self.depth_intrinsics.height).max(0) as usize;
+        if depth_data.len() < size {
+            return None;
+        }
+
+        //librealsense does not bounds check and can read one row plus a pixel past the frame.
+        //Zero depth is skipped by the search, so padding with zeros keeps the result the same
+        let mut padded = Vec::with_capacity(size + self.depth_intrinsics.width as usize + 1);
+        padded.extend_from_slice(&depth_data[..size]);
+        padded.resize(padded.capacity(), 0);
+
+        let depth_intrinsics = rs2_intrinsics::from(self.depth_intrinsics);
+        let color_intrinsics = rs2_intrinsics::from(self.color_intrinsics);
+        let color_to_depth = rs2_extrinsics::from(self.color_to_depth);
+        let depth_to_color = rs2_extrinsics::from(self.depth_to_color);
+
+        //Left untouched by librealsense when nothing is found
+        let mut to_pixel = [f32::NAN; 2];
+        unsafe {
+            rs2_project_color_pixel_to_depth_pixel(
+                to_pixel.as_mut_ptr(),
+                padded.as_ptr(),
+                self.depth_scale,
+                self.depth_min,
+                self.depth_max,
+                &depth_intrinsics,
+                &color_intrinsics,
+                &color_to_depth,
+                &depth_to_color,
+                pixel.as_ptr(),
+            );
+        }
+
+        if to_pixel[0].is_nan() {
+            return None;
+        }
+        return Some(to_pixel);
+    }
+
+    //Where the color pixel at the given depth lands in the depth image, clamped to it
+    fn depth_pixel_at(&self, pixel: [f32; 2], depth: f32) -> [f32; 2] {
+        let point = deproject_pixel_to_point(&self.color_intrinsics, pixel, depth);
+        let point = transform_point_to_point(&self.color_to_depth, point);
+        let depth_pixel = project_point_to_pixel(&self.depth_intrinsics, point);
+
+        return [
+            depth_pixel[0].clamp(0.0, self.depth_intrinsics.width as f32),
+            depth_pixel[1].clamp(0.0, self.depth_intrinsics.height as f32),
+        ];
+    }
+}
+
+fn next_pixel_in_line(current: [f32; 2], start: [f32; 2], end: [f32; 2]) -> [f32; 2] {
+    let slope = (end[1] - start[1]) / (end[0] - start[0]);
+    let [mut x, mut y] = current;
+
+    if (end[0] - x).abs() > (end[1] - y).abs() {
+        x = if end[0] > x { x + 1.0 } else { x - 1.0 };
+        y = end[1] - slope * (end[0] - x);
+    } else {
+        y = if end[1] > y { y + 1.0 } else { y - 1.0 };
+        x = end[0] - (end[1] - y) / slope;
+    }
+
+    return [x, y];
+}
+
+fn is_pixel_in_line(current: [f32; 2], start: [f32; 2], end: [f32; 2]) -> bool {
+    let between = |axis: usize| {
+        (end[axis] >= start[axis] && end[axis] >= current[axis] && current[axis] >= start[axis])
+            || (end[axis] <= start[axis]
+                && end[axis] <= current[axis]
+                && current[axis] <= start[axis])
+    };
+
+    return between(0) && between(1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const TOLERANCE: f32 = 1e-3;
+
+    const MODELS: [(Distortion, [f32; 5]); 6] = [
+        (Distortion::None, [0.0; 5]),
+        (
+            Distortion::ModifiedBrownConrady,
+            [0.1, -0.05, 0.001, -0.002, 0.01],
+        ),
+        (
+            Distortion::InverseBrownConrady,
+            [0.1, -0.05, 0.001, -0.002, 0.01],
+        ),
+        (
+            Distortion::BrownConrady,
+            [-0.05, 0.06, 0.0005, 0.0007, -0.02],
+        ),
+        (Distortion::FTheta, [0.9, 0.0, 0.0, 0.0, 0.0]),
+        (Distortion::KannalaBrandt4, [-0.01, 0.04, -0.04, 0.008, 0.0]),
+    ];
+
+    const POINTS: [[f32; 3]; 3] = [[0.0, 0.0, 1.0], [0.2, -0.1, 0.8], [-0.5, 0.3, 2.5]];
+
+    const PIXELS: [[f32; 2]; 4] = [[0.0, 0.0], [321.5, 238.25], [600.0, 50.0], [100.0, 400.0]];
+
+    //Made up, roughly what a D435 color stream reports
+    fn intrinsics(model: Distortion, coeffs: [f32; 5]) -> Intrinsics {
+        Intrinsics {
+            width: 640,
+            height: 480,
+            ppx: 321.5,
+            ppy: 238.25,
+            fx: 615.0,
+            fy: 614.5,
+            model,
+            coeffs,
+        }
+    }
+
+    fn is_close(rust: &[f32], c: &[f32]) -> bool {
+        rust.iter()
+            .zip(c)
+            .all(|(rust, c)| (rust - c).abs() <= TOLERANCE * c.abs().max(1.0))
+    }
+
+    #[test]
+    fn project_matches_librealsense() {
+        for (model, coeffs) in MODELS {
+            let intrinsics = intrinsics(model, coeffs);
+            let rs2_intrinsics = rs2_intrinsics::from(intrinsics);
+
+            let rust: Vec<[f32; 2]> = POINTS
+                .iter()
+                .map(|point| project_point_to_pixel(&intrinsics, *point))
+                .collect();
+            let c: Vec<[f32; 2]> = POINTS
+                .iter()
+                .map(|point| {
+                    let mut pixel = [0.0f32; 2];
+                    unsafe {
+                        rs2_project_point_to_pixel(
+                            pixel.as_mut_ptr(),
+                            &rs2_intrinsics,
+                            point.as_ptr(),
+                        );
+                    }
+                    pixel
+                })
+                .collect();
+
+            assert!(
+                is_close(rust.concat().as_slice(), c.concat().as_slice()),
+                "{} project differs, rust {:?} c {:?}",
+                model,
+                rust,
+                c
+            );
+        }
+    }
+
+    #[test]
+    fn deproject_matches_librealsense() {
+        //Librealsense can not deproject forward distorted images
+        for (model, coeffs) in MODELS
+            .into_iter()
+            .filter(|(model, _)| *model != Distortion::ModifiedBrownConrady)
+        {
+            let intrinsics = intrinsics(model, coeffs);
+            let rs2_intrinsics = rs2_intrinsics::from(intrinsics);
+
+            let rust: Vec<[f32; 3]> = PIXELS
+                .iter()
+                .map(|pixel| deproject_pixel_to_point(&intrinsics, *pixel, 1.5))
+                .collect();
+            let c: Vec<[f32; 3]> = PIXELS
+                .iter()
+                .map(|pixel| {
+                    let mut point = [0.0f32; 3];
+                    unsafe {
+                        rs2_deproject_pixel_to_point(
+                            point.as_mut_ptr(),
+                            &rs2_intrinsics,
+                            pixel.as_ptr(),
+                            1.5,
+                        );
+                    }
+                    point
+                })
+                .collect();
+
+            assert!(
+                is_close(rust.concat().as_slice(), c.concat().as_slice()),
+                "{} deproject differs, rust {:?} c {:?}",
+                model,
+                rust,
+                c
+            );
+        }
+    }
+
+    #[test]
+    fn deproject_modified_brown_conrady_as_undistorted() {
+        let (model, coeffs) = MODELS[1];
+        let distorted = intrinsics(model, coeffs);
+        let undistorted = intrinsics(Distortion::None, coeffs);
+
+        for pixel in PIXELS {
+            assert_eq!(
+                deproject_pixel_to_point(&distorted, pixel, 1.5),
+                deproject_pixel_to_point(&undistorted, pixel, 1.5)
+            );
+        }
+    }
+
+    #[test]
+    fn transform_matches_librealsense() {
+        let extrinsics = Extrinsics {
+            rotation: [0.999, 0.01, -0.04, -0.01, 0.999, 0.005, 0.04, -0.005, 0.999],
+            translation: [0.015, 0.0002, -0.0001],
+        };
+
+        let rust: Vec<[f32; 3]> = POINTS
+            .iter()
+            .map(|point| transform_point_to_point(&extrinsics, *point))
+            .collect();
+        let c: Vec<[f32; 3]> = POINTS
+            .iter()
+            .map(|point| {
+                let mut transformed = [0.0f32; 3];
+                unsafe {
+                    rs2_transform_point_to_point(
+                        transformed.as_mut_ptr(),
+                        &rs2_extrinsics::from(extrinsics),
+                        point.as_ptr(),
+                    );
+                }
+                transformed
+            })
+            .collect();
+
+        assert!(
+            is_close(rust.concat().as_slice(), c.concat().as_slice()),
+            "transform differs, rust {:?} c {:?}",
+            rust,
+            c
+        );
+    }
+}
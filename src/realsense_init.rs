@@ -1,73 +1,232 @@
-use std::collections::{self, VecDeque};
+use std::{
+    collections::VecDeque,
+    ffi::CString,
+    os::raw::c_void,
+    panic::{self, AssertUnwindSafe},
+    sync::{mpsc, Condvar, Mutex},
+};
 
 use crate::{
-    bindings::*, check_error, format::Rs2Format, print_device_info, stream::Rs2StreamKind,
-    FrameData, FrameInfo, ImageData, RealsenseError, SafeFrame,
+    bindings::*, camera_info::Rs2CameraInfo, check_error, format::Rs2Format, stream::Rs2StreamKind,
+    Context, Device, DeviceInfo, FrameData, FrameInfo, Frameset, ImageData, PipelineConfig,
+    PipelineProfile, RealsenseError, RealsenseSource, RecoveryPolicy, SafeFrame, StreamProfile,
+    Supervisor,
 };
 
 pub struct RealsenseInstance {
-    pub context: *mut rs2_context,
-    pub device: *mut rs2_device,
+    //Fields drop in declaration order, the device has to go before the context it came from
+    pub device: Device,
+    pub context: Context,
     pub pipeline: *mut rs2_pipeline,
+    //The config the pipeline was last started with, pinned to the device's serial
     pub config: *mut rs2_config,
-    pub pipeline_profile: *mut rs2_pipeline_profile,
+    //None while the pipeline is not streaming
+    pub pipeline_profile: Option<PipelineProfile>,
+    //Set by start_with_callback, kept until the next start so restart can hand it to librealsense again
+    frame_callback: Option<Box<FrameCallback>>,
 }
 
 unsafe impl Sync for RealsenseInstance {}
 
 unsafe impl Send for RealsenseInstance {}
 
+#[derive(Debug, Clone, Default)]
+pub struct RealsenseInstanceBuilder {
+    serial: Option<String>,
+}
+
+//Roughly what librealsense keeps in its own frame pool per stream, holding on to more stalls the device
+pub const DEFAULT_FRAME_BUFFER_CAPACITY: usize = 16;
+
+//What a full FrameBuffer does with the next frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum DropPolicy {
+    //Makes room by dropping the frame that has waited the longest
+    #[default]
+    DropOldest,
+    //Drops the incoming frame
+    DropNewest,
+    //Waits until get_curr_frame makes room, only useful when another thread is consuming
+    Block,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub struct FrameBufferStats {
+    pub enqueued: u64,
+    pub dropped: u64,
+    pub consumed: u64,
+    //Frames waiting right now
+    pub queued: usize,
+}
+
+//Can be shared between a producer, like a start_with_callback closure, and a consumer through an Arc
 pub struct FrameBuffer {
-    queue: collections::VecDeque<SafeFrame>,
-    // next_frame: ImageData,
+    queue: Mutex<FrameQueue>,
+    not_full: Condvar,
+    capacity: usize,
+    policy: DropPolicy,
+}
+
+struct FrameQueue {
+    frames: VecDeque<SafeFrame>,
+    stats: FrameBufferStats,
 }
 
 unsafe impl Sync for FrameBuffer {}
 
 unsafe impl Send for FrameBuffer {}
 
+//Handed to librealsense as the user pointer of the frame callback
+struct FrameCallback {
+    callback: Box<dyn FnMut(Result<Frameset, RealsenseError>) + Send>,
+}
+
+unsafe extern "C" fn frame_callback_trampoline(frame: *mut rs2_frame, user: *mut c_void) {
+    //The callback owns the frame, SafeFrame releases it once the user is done with it
+    let frame = SafeFrame { frame };
+
+    //Panicking across the FFI boundary aborts, so anything the user closure throws stops here
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        ((*user.cast::<FrameCallback>()).callback)(Frameset::new(frame))
+    }));
+
+    if result.is_err() {
+        log::error!("Frame callback panicked");
+    }
+}
+
+impl RealsenseInstanceBuilder {
+    pub fn serial(mut self, serial: &str) -> Self {
+        self.serial = Some(serial.to_string());
+        self
+    }
+
+    //Without a serial this opens the first enumerated device, same as new
+    pub fn build(self) -> Result<RealsenseInstance, RealsenseError> {
+        let context = Context::new()?;
+
+        let info = match self.serial {
+            Some(serial) => context
+                .devices()?
+                .into_iter()
+                .find(|info| info.serial_number.as_deref() == Some(serial.as_str()))
+                .ok_or(RealsenseError::DeviceNotFound { serial })?,
+            None => {
+                let devices = context.devices()?;
+                log::debug!("Device count is {}", devices.len());
+                devices.into_iter().next().ok_or(RealsenseError::NoDevice)?
+            }
+        };
+
+        return RealsenseInstance::open(context, &info);
+    }
+}
+
 impl RealsenseInstance {
-    //This might want to take in index, in the case of more than 1 device
+    pub fn builder() -> RealsenseInstanceBuilder {
+        RealsenseInstanceBuilder::default()
+    }
+
+    //Opens whichever device librealsense enumerates first, use open to pick a specific one.
+    //Panics if that fails, try_new is the fallible version
     pub fn new() -> Self {
+        Self::try_new().expect("Failed to open a realsense device")
+    }
+
+    pub fn try_new() -> Result<Self, RealsenseError> {
+        Self::builder().build()
+    }
+
+    //info should come from context.devices(), the pipeline is pinned to that device's serial
+    pub fn open(context: Context, info: &DeviceInfo) -> Result<Self, RealsenseError> {
+        let device = context.open_device(info)?;
+        let config = device_config(&device)?;
+
         unsafe {
             let mut error = std::ptr::null_mut::<rs2_error>();
 
-            let context = rs2_create_context(RS2_API_VERSION as i32, &mut error);
-            check_error(error);
-
-            let device_list = rs2_query_devices(context, &mut error);
+            let pipeline = rs2_create_pipeline(context.context, &mut error);
+            if let Err(err) = check_error(error) {
+                rs2_delete_config(config);
+                return Err(err);
+            }
 
-            let device_count = rs2_get_device_count(device_list, &mut error);
-            check_error(error);
+            rs2_free_error(error);
 
-            if device_count == 0 {
-                println!("No devices connected");
-                //return error
-            } else {
-                println!("Device count is {}", device_count);
+            //Fields the device does not report show up as None
+            match DeviceInfo::new(&device, info.index) {
+                Ok(device_info) => log::info!("Using device: {:?}", device_info),
+                Err(err) => log::warn!("Could not read the device info: {}", err),
             }
 
-            let device = rs2_create_device(device_list, 0, &mut error);
-            check_error(error);
-            print_device_info(device);
+            return Ok(RealsenseInstance {
+                context,
+                device,
+                pipeline,
+                config,
+                pipeline_profile: None,
+                frame_callback: None,
+            });
+        }
+    }
 
-            let pipeline = rs2_create_pipeline(context, &mut error);
-            check_error(error);
+    //Checks the whole config can be resolved against the device before starting anything.
+    //Fails if the pipeline is already streaming, use reconfigure for that
+    pub fn start(&mut self, config: &PipelineConfig) -> Result<&PipelineProfile, RealsenseError> {
+        self.start_config(config, &mut None)
+    }
 
-            let config = rs2_create_config(&mut error);
-            check_error(error);
+    //Same as start, except every frameset is pushed to the callback on a librealsense thread instead of
+    //being waited for. Panics in the callback are caught and logged. wait_for_frames does not work while it is set
+    pub fn start_with_callback<F>(
+        &mut self,
+        config: &PipelineConfig,
+        mut callback: F,
+    ) -> Result<&PipelineProfile, RealsenseError>
+    where
+        F: FnMut(Frameset) + Send + 'static,
+    {
+        self.start_with_fallible_callback(config, move |frameset| match frameset {
+            Ok(frameset) => callback(frameset),
+            Err(err) => log::warn!("Dropping a frameset that could not be read: {}", err),
+        })
+    }
 
-            rs2_delete_device_list(device_list); //might cause errors if you delete the device_list but keep the device
-            rs2_free_error(error);
+    //For callers that want to hear about framesets that could not be read instead of having them logged
+    pub(crate) fn start_with_fallible_callback<F>(
+        &mut self,
+        config: &PipelineConfig,
+        callback: F,
+    ) -> Result<&PipelineProfile, RealsenseError>
+    where
+        F: FnMut(Result<Frameset, RealsenseError>) + Send + 'static,
+    {
+        let frame_callback = Box::new(FrameCallback {
+            callback: Box::new(callback),
+        });
+
+        self.start_config(config, &mut Some(frame_callback))
+    }
 
-            RealsenseInstance {
-                context: context,
-                device: device,
-                pipeline: pipeline,
-                config: config,
-                pipeline_profile: std::ptr::null_mut::<rs2_pipeline_profile>(),
+    //start_with_callback feeding a channel. Holds at most capacity framesets, once the receiver falls that far
+    //behind new framesets are dropped so librealsense's frame pool never runs dry
+    pub fn start_with_channel(
+        &mut self,
+        config: &PipelineConfig,
+        capacity: usize,
+    ) -> Result<mpsc::Receiver<Frameset>, RealsenseError> {
+        //A zero sized sync_channel only hands over framesets to a receiver that is already waiting
+        let (sender, receiver) = mpsc::sync_channel(capacity.max(1));
+
+        self.start_with_callback(config, move |frames| match sender.try_send(frames) {
+            Err(mpsc::TrySendError::Full(_)) => {
+                log::trace!("Frame channel is full, dropping frameset")
             }
-        }
+            //Nobody is listening anymore, nothing to do
+            Err(mpsc::TrySendError::Disconnected(_)) | Ok(()) => {}
+        })?;
+
+        return Ok(receiver);
     }
 
     pub fn stream_frames(
@@ -78,81 +237,284 @@ impl RealsenseInstance {
         fps: i32,
         stream: Rs2StreamKind, //rs2_stream_RS2_STREAM_COLOR//
         format: Rs2Format,     //rs2_format_RS2_FORMAT_RGB8//
-    ) {
+    ) -> Result<(), RealsenseError> {
+        let config =
+            PipelineConfig::new().enable_stream(stream, stream_index, width, height, format, fps);
+        self.start(&config)?;
+        return Ok(());
+    }
+
+    //Switches resolution, frame rate or streams without recreating the context or device. A callback or channel
+    //set up by start_with_callback keeps receiving framesets from the new config.
+    //If the new config fails to start, the previous one is brought back up before returning the error
+    pub fn reconfigure(
+        &mut self,
+        config: &PipelineConfig,
+    ) -> Result<&PipelineProfile, RealsenseError> {
+        let was_streaming = self.is_streaming();
+        self.stop();
+
+        let mut frame_callback = self.frame_callback.take();
+        if let Err(err) = self.start_config(config, &mut frame_callback) {
+            //Still needed to bring the previous config back up
+            self.frame_callback = frame_callback;
+
+            if was_streaming {
+                if let Err(restart_err) = self.restart() {
+                    log::warn!("Failed to restart the previous config: {}", restart_err);
+                }
+            }
+            return Err(err);
+        }
+
+        return Ok(self.pipeline_profile.as_ref().unwrap());
+    }
+
+    //What the running pipeline actually negotiated for every stream
+    pub fn active_profile(&self) -> Result<Vec<StreamProfile>, RealsenseError> {
         unsafe {
             let mut error = std::ptr::null_mut::<rs2_error>();
 
-            // This should be cool? I hope rust analyzer isn't lying to me
-            rs2_config_enable_stream(
-                self.config,
-                stream as u32,
-                stream_index,
-                width,
-                height,
-                format as u32,
-                fps,
-                &mut error,
-            );
-
-            self.pipeline_profile =
-                rs2_pipeline_start_with_config(self.pipeline, self.config, &mut error);
-            check_error(error);
+            let profile = rs2_pipeline_get_active_profile(self.pipeline, &mut error);
+            check_error(error)?;
+
             rs2_free_error(error);
+            return PipelineProfile { profile }.streams();
         }
     }
 
-    //This should take in the parameters to configure the stream
-}
+    pub fn is_streaming(&self) -> bool {
+        self.pipeline_profile.is_some()
+    }
 
-impl FrameBuffer {
-    //please don't call get_curr_frame before calling stream_frames
-    pub fn new() -> Self {
-        FrameBuffer {
-            queue: VecDeque::new(),
-        }
+    //Hands the instance to a Supervisor that brings the pipeline back after disconnects or repeated timeouts.
+    //The supervisor listens for disconnects through the context's devices changed callback, which replaces
+    //any callback already registered with Context::set_devices_changed_callback or device_events
+    pub fn supervise(
+        self,
+        policy: RecoveryPolicy,
+    ) -> Result<Supervisor<RealsenseSource>, RealsenseError> {
+        return Ok(Supervisor::new(RealsenseSource::new(self)?, policy));
     }
 
-    pub fn populate_queue(
-        &mut self,
-        realsense: &mut RealsenseInstance,
-    ) -> Result<(), RealsenseError> {
+    //Blocks until the pipeline hands over the next set of frames
+    pub fn wait_for_frames(&mut self, timeout_ms: u32) -> Result<Frameset, RealsenseError> {
         unsafe {
             let mut error = std::ptr::null_mut::<rs2_error>();
 
-            let frames =
-                rs2_pipeline_wait_for_frames(realsense.pipeline, RS2_DEFAULT_TIMEOUT, &mut error);
+            let frames = rs2_pipeline_wait_for_frames(self.pipeline, timeout_ms, &mut error);
             check_error(error)?;
 
-            //This num_frame is something worth investigating
-            let num_of_frames = rs2_embedded_frames_count(frames, &mut error);
-            check_error(error)?;
+            rs2_free_error(error);
+            return Frameset::new(SafeFrame { frame: frames });
+        }
+    }
 
-            for i in 0..num_of_frames {
-                let frame = rs2_extract_frame(frames, 0, &mut error);
+    //Stops the pipeline and starts it again with everything enabled on the config so far,
+    //still delivering to the callback if it was started with one
+    pub fn restart(&mut self) -> Result<(), RealsenseError> {
+        self.stop();
 
-                check_error(error)?;
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
 
-                self.queue.push_back(SafeFrame { frame });
-            }
-            rs2_release_frame(frames);
+            let profile = match self.frame_callback.as_mut() {
+                Some(callback) => rs2_pipeline_start_with_config_and_callback(
+                    self.pipeline,
+                    self.config,
+                    Some(frame_callback_trampoline),
+                    (callback.as_mut() as *mut FrameCallback).cast::<c_void>(),
+                    &mut error,
+                ),
+                None => rs2_pipeline_start_with_config(self.pipeline, self.config, &mut error),
+            };
+            check_error(error)?;
+
+            self.pipeline_profile = Some(PipelineProfile { profile });
             rs2_free_error(error);
             return Ok(());
         }
     }
 
-    pub fn get_curr_frame(&mut self) -> Option<ImageData> {
+    //The callback is only taken once the pipeline started, on failure it is left with the caller
+    fn start_config(
+        &mut self,
+        config: &PipelineConfig,
+        frame_callback: &mut Option<Box<FrameCallback>>,
+    ) -> Result<&PipelineProfile, RealsenseError> {
+        let rs2_config = device_config(&self.device)?;
+
+        unsafe {
+            match start_pipeline(
+                self.pipeline,
+                rs2_config,
+                config,
+                frame_callback.as_deref_mut(),
+            ) {
+                Ok(profile) => {
+                    rs2_delete_config(self.config);
+                    self.config = rs2_config;
+                    //librealsense is done with the previous callback, if there was one
+                    self.frame_callback = frame_callback.take();
+                    return Ok(self.pipeline_profile.insert(profile));
+                }
+                Err(err) => {
+                    rs2_delete_config(rs2_config);
+                    return Err(err);
+                }
+            }
+        }
+    }
+
+    //Does nothing if the pipeline was never started or already stopped.
+    //A disconnected device makes rs2_pipeline_stop fail, there is nothing left to do about it so it only gets logged
+    pub fn stop(&mut self) {
+        if let Some(profile) = self.pipeline_profile.take() {
+            unsafe {
+                let mut error = std::ptr::null_mut::<rs2_error>();
+
+                rs2_pipeline_stop(self.pipeline, &mut error);
+                if let Err(err) = check_error(error) {
+                    log::warn!("Failed to stop the pipeline: {}", err);
+                }
+
+                rs2_free_error(error);
+            }
+            drop(profile);
+        }
+    }
+}
+
+//Every config starts out pinned to the device, otherwise the pipeline streams from whichever device it resolves first
+fn device_config(device: &Device) -> Result<*mut rs2_config, RealsenseError> {
+    let serial = device.info(Rs2CameraInfo::SerialNumber)?;
+
+    unsafe {
+        let mut error = std::ptr::null_mut::<rs2_error>();
+
+        let config = rs2_create_config(&mut error);
+        check_error(error)?;
+
+        if let Some(serial) = serial {
+            let serial = CString::new(serial).unwrap();
+            rs2_config_enable_device(config, serial.as_ptr(), &mut error);
+            if let Err(err) = check_error(error) {
+                rs2_delete_config(config);
+                return Err(err);
+            }
+        }
+
+        rs2_free_error(error);
+        return Ok(config);
+    }
+}
+
+unsafe fn start_pipeline(
+    pipeline: *mut rs2_pipeline,
+    rs2_config: *mut rs2_config,
+    config: &PipelineConfig,
+    frame_callback: Option<&mut FrameCallback>,
+) -> Result<PipelineProfile, RealsenseError> {
+    let mut error = std::ptr::null_mut::<rs2_error>();
+
+    config.apply(rs2_config)?;
+
+    let can_resolve = rs2_config_can_resolve(rs2_config, pipeline, &mut error);
+    check_error(error)?;
+
+    if can_resolve == 0 {
+        return Err(RealsenseError::ConfigCannotResolve);
+    }
+
+    let profile = match frame_callback {
+        Some(callback) => rs2_pipeline_start_with_config_and_callback(
+            pipeline,
+            rs2_config,
+            Some(frame_callback_trampoline),
+            (callback as *mut FrameCallback).cast::<c_void>(),
+            &mut error,
+        ),
+        None => rs2_pipeline_start_with_config(pipeline, rs2_config, &mut error),
+    };
+    check_error(error)?;
+
+    rs2_free_error(error);
+    return Ok(PipelineProfile { profile });
+}
+
+impl FrameBuffer {
+    //please don't call get_curr_frame before calling stream_frames
+    pub fn new() -> Self {
+        FrameBuffer::with_capacity(DEFAULT_FRAME_BUFFER_CAPACITY, DropPolicy::default())
+    }
+
+    //A capacity of 0 is treated as 1
+    pub fn with_capacity(capacity: usize, policy: DropPolicy) -> Self {
+        let capacity = capacity.max(1);
+
+        FrameBuffer {
+            queue: Mutex::new(FrameQueue {
+                frames: VecDeque::with_capacity(capacity),
+                stats: FrameBufferStats::default(),
+            }),
+            not_full: Condvar::new(),
+            capacity,
+            policy,
+        }
+    }
+
+    pub fn populate_queue(&self, realsense: &mut RealsenseInstance) -> Result<(), RealsenseError> {
+        let frames = realsense.wait_for_frames(RS2_DEFAULT_TIMEOUT)?;
+        self.push_frames(frames);
+        return Ok(());
+    }
+
+    //Queues every frame of the set one after another, use the Frameset itself to keep them together
+    pub fn push_frames(&self, frames: Frameset) {
+        for frame in frames {
+            self.push_frame(frame);
+        }
+    }
+
+    pub fn push_frame(&self, frame: SafeFrame) {
+        let mut queue = self.queue.lock().unwrap();
+
+        if queue.frames.len() >= self.capacity {
+            match self.policy {
+                DropPolicy::DropOldest => {
+                    queue.frames.pop_front();
+                    queue.stats.dropped += 1;
+                }
+                DropPolicy::DropNewest => {
+                    queue.stats.dropped += 1;
+                    return;
+                }
+                DropPolicy::Block => {
+                    queue = self
+                        .not_full
+                        .wait_while(queue, |queue| queue.frames.len() >= self.capacity)
+                        .unwrap();
+                }
+            }
+        }
+
+        queue.frames.push_back(frame);
+        queue.stats.enqueued += 1;
+    }
+
+    pub fn get_curr_frame(&self) -> Option<ImageData> {
         //check if the frame_info and frame_data are valid before making ImageData
 
         let mut frame_info = FrameInfo::default();
 
-        if let Some(front) = self.queue.pop_front() {
+        if let Some(front) = self.pop_frame() {
             if let Ok(current) = FrameInfo::new(&front) {
                 frame_info = current;
             } else {
                 return None;
             }
 
-            println!("get_curr_frame frame_info format :{:?}", frame_info.format);
+            log::trace!("get_curr_frame frame_info format :{:?}", frame_info.format);
 
             if let Ok(data) = FrameData::new(
                 &front,
@@ -168,21 +530,51 @@ impl FrameBuffer {
             return None;
         }
     }
+
+    //The raw frame, for when ImageData is not what you are after
+    pub fn pop_frame(&self) -> Option<SafeFrame> {
+        let mut queue = self.queue.lock().unwrap();
+
+        let frame = queue.frames.pop_front()?;
+        queue.stats.consumed += 1;
+        self.not_full.notify_one();
+
+        return Some(frame);
+    }
+
+    pub fn len(&self) -> usize {
+        self.queue.lock().unwrap().frames.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn policy(&self) -> DropPolicy {
+        self.policy
+    }
+
+    pub fn stats(&self) -> FrameBufferStats {
+        let queue = self.queue.lock().unwrap();
+
+        return FrameBufferStats {
+            queued: queue.frames.len(),
+            ..queue.stats
+        };
+    }
 }
 
 impl Drop for RealsenseInstance {
     fn drop(&mut self) {
-        unsafe {
-            let mut error = std::ptr::null_mut::<rs2_error>();
+        self.stop();
 
-            rs2_pipeline_stop(self.pipeline, &mut error);
-            check_error(error);
-            rs2_delete_pipeline_profile(self.pipeline_profile);
+        unsafe {
             rs2_delete_config(self.config);
             rs2_delete_pipeline(self.pipeline);
-            rs2_delete_device(self.device);
-            rs2_delete_context(self.context);
-            rs2_free_error(error);
         }
     }
 }
@@ -0,0 +1,105 @@
+use crate::{bindings::*, check_error, RealsenseError, Sensor};
+
+//Pixel rectangle with inclusive bounds, the whole 640x480 image is 0, 0, 639, 479
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct RegionOfInterest {
+    pub min_x: i32,
+    pub min_y: i32,
+    pub max_x: i32,
+    pub max_y: i32,
+}
+
+impl RegionOfInterest {
+    pub fn new(min_x: i32, min_y: i32, max_x: i32, max_y: i32) -> Self {
+        RegionOfInterest {
+            min_x,
+            min_y,
+            max_x,
+            max_y,
+        }
+    }
+
+    pub fn width(&self) -> i32 {
+        self.max_x - self.min_x + 1
+    }
+
+    pub fn height(&self) -> i32 {
+        self.max_y - self.min_y + 1
+    }
+
+    //Whether the rectangle is non empty and lies inside a width x height image
+    pub fn fits(&self, width: i32, height: i32) -> bool {
+        0 <= self.min_x
+            && self.min_x <= self.max_x
+            && self.max_x < width
+            && 0 <= self.min_y
+            && self.min_y <= self.max_y
+            && self.max_y < height
+    }
+}
+
+//Auto exposure region of interest of a sensor that supports it, see Sensor::as_roi_sensor.
+//librealsense only accepts a region while the sensor is streaming
+pub struct RoiSensor<'a> {
+    pub sensor: &'a Sensor,
+}
+
+impl<'a> RoiSensor<'a> {
+    pub fn region_of_interest(&self) -> Result<RegionOfInterest, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let mut roi = RegionOfInterest::new(0, 0, 0, 0);
+            rs2_get_region_of_interest(
+                self.sensor.sensor,
+                &mut roi.min_x,
+                &mut roi.min_y,
+                &mut roi.max_x,
+                &mut roi.max_y,
+                &mut error,
+            );
+            check_error(error)?;
+
+            rs2_free_error(error);
+            return Ok(roi);
+        }
+    }
+
+    //Checked against the resolution the sensor is streaming at before anything is sent to the device
+    pub fn set_region_of_interest(&self, roi: RegionOfInterest) -> Result<(), RealsenseError> {
+        let (width, height) = self
+            .active_resolution()?
+            .ok_or(RealsenseError::SensorNotStreaming)?;
+
+        if !roi.fits(width, height) {
+            return Err(RealsenseError::InvalidRegionOfInterest { roi, width, height });
+        }
+
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            rs2_set_region_of_interest(
+                self.sensor.sensor,
+                roi.min_x,
+                roi.min_y,
+                roi.max_x,
+                roi.max_y,
+                &mut error,
+            );
+            check_error(error)?;
+
+            rs2_free_error(error);
+            return Ok(());
+        }
+    }
+
+    //Resolution of the first video stream the sensor has open, None when it is not streaming
+    pub fn active_resolution(&self) -> Result<Option<(i32, i32)>, RealsenseError> {
+        return Ok(self
+            .sensor
+            .active_streams()?
+            .iter()
+            .find(|profile| profile.width > 0 && profile.height > 0)
+            .map(|profile| (profile.width, profile.height)));
+    }
+}
@@ -0,0 +1,316 @@
+use crate::{
+    bindings::*,
+    camera_info::Rs2CameraInfo,
+    check_error,
+    extension::Rs2SensorExtension,
+    option::{OptionRange, Rs2Option},
+    profiles_in_list,
+    visual_preset::VisualPreset,
+    DepthSensor, RealsenseError, RoiSensor, StreamProfile,
+};
+use num_traits::FromPrimitive;
+use std::ffi::CStr;
+
+pub struct Sensor {
+    pub sensor: *mut rs2_sensor,
+}
+
+unsafe impl Sync for Sensor {}
+
+unsafe impl Send for Sensor {}
+
+impl Sensor {
+    //Returns None when the sensor does not report this kind of info
+    pub fn info(&self, info: Rs2CameraInfo) -> Result<Option<String>, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let supported = rs2_supports_sensor_info(self.sensor, info as u32, &mut error);
+            check_error(error)?;
+
+            if supported == 0 {
+                rs2_free_error(error);
+                return Ok(None);
+            }
+
+            let value = rs2_get_sensor_info(self.sensor, info as u32, &mut error);
+            check_error(error)?;
+
+            rs2_free_error(error);
+
+            return Ok(Some(CStr::from_ptr(value).to_string_lossy().into_owned()));
+        }
+    }
+
+    pub fn name(&self) -> Result<Option<String>, RealsenseError> {
+        self.info(Rs2CameraInfo::Name)
+    }
+
+    pub fn is_extendable_to(&self, extension: Rs2SensorExtension) -> Result<bool, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let extendable = rs2_is_sensor_extendable_to(self.sensor, extension as u32, &mut error);
+            check_error(error)?;
+
+            rs2_free_error(error);
+            return Ok(extendable != 0);
+        }
+    }
+
+    pub fn is_depth_sensor(&self) -> Result<bool, RealsenseError> {
+        self.is_extendable_to(Rs2SensorExtension::DepthSensor)
+    }
+
+    pub fn is_depth_stereo_sensor(&self) -> Result<bool, RealsenseError> {
+        self.is_extendable_to(Rs2SensorExtension::DepthStereoSensor)
+    }
+
+    pub fn is_color_sensor(&self) -> Result<bool, RealsenseError> {
+        self.is_extendable_to(Rs2SensorExtension::ColorSensor)
+    }
+
+    pub fn is_motion_sensor(&self) -> Result<bool, RealsenseError> {
+        self.is_extendable_to(Rs2SensorExtension::MotionSensor)
+    }
+
+    pub fn is_roi_sensor(&self) -> Result<bool, RealsenseError> {
+        self.is_extendable_to(Rs2SensorExtension::Roi)
+    }
+
+    //Every mode this sensor can stream
+    pub fn stream_profiles(&self) -> Result<Vec<StreamProfile>, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let list = rs2_get_stream_profiles(self.sensor, &mut error);
+            check_error(error)?;
+
+            let profiles = profiles_in_list(list);
+            rs2_delete_stream_profiles_list(list);
+
+            rs2_free_error(error);
+            return profiles;
+        }
+    }
+
+    //The modes the sensor is streaming right now, empty when it is stopped
+    pub fn active_streams(&self) -> Result<Vec<StreamProfile>, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let list = rs2_get_active_streams(self.sensor, &mut error);
+            check_error(error)?;
+
+            let profiles = profiles_in_list(list);
+            rs2_delete_stream_profiles_list(list);
+
+            rs2_free_error(error);
+            return profiles;
+        }
+    }
+
+    //None when the sensor does not produce depth
+    pub fn as_depth_sensor(&self) -> Result<Option<DepthSensor<'_>>, RealsenseError> {
+        if !self.is_depth_sensor()? {
+            return Ok(None);
+        }
+
+        return Ok(Some(DepthSensor { sensor: self }));
+    }
+
+    //None when the sensor has no auto exposure region of interest
+    pub fn as_roi_sensor(&self) -> Result<Option<RoiSensor<'_>>, RealsenseError> {
+        if !self.is_roi_sensor()? {
+            return Ok(None);
+        }
+
+        return Ok(Some(RoiSensor { sensor: self }));
+    }
+}
+
+//Options
+impl Sensor {
+    //Every sensor is also an rs2_options as far as librealsense is concerned
+    fn options(&self) -> *const rs2_options {
+        self.sensor.cast::<rs2_options>()
+    }
+
+    pub fn supports_option(&self, option: Rs2Option) -> Result<bool, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let supported = rs2_supports_option(self.options(), option as u32, &mut error);
+            check_error(error)?;
+
+            rs2_free_error(error);
+            return Ok(supported != 0);
+        }
+    }
+
+    pub fn get_option(&self, option: Rs2Option) -> Result<f32, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let value = rs2_get_option(self.options(), option as u32, &mut error);
+            check_error(error)?;
+
+            rs2_free_error(error);
+            return Ok(value);
+        }
+    }
+
+    pub fn set_option(&self, option: Rs2Option, value: f32) -> Result<(), RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            rs2_set_option(self.options(), option as u32, value, &mut error);
+            check_error(error)?;
+
+            rs2_free_error(error);
+            return Ok(());
+        }
+    }
+
+    pub fn option_range(&self, option: Rs2Option) -> Result<OptionRange, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let mut range = OptionRange {
+                min: 0.0,
+                max: 0.0,
+                step: 0.0,
+                default: 0.0,
+            };
+
+            rs2_get_option_range(
+                self.options(),
+                option as u32,
+                &mut range.min,
+                &mut range.max,
+                &mut range.step,
+                &mut range.default,
+                &mut error,
+            );
+            check_error(error)?;
+
+            rs2_free_error(error);
+            return Ok(range);
+        }
+    }
+
+    pub fn is_read_only(&self, option: Rs2Option) -> Result<bool, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let read_only = rs2_is_option_read_only(self.options(), option as u32, &mut error);
+            check_error(error)?;
+
+            rs2_free_error(error);
+            return Ok(read_only != 0);
+        }
+    }
+
+    //Options this version of the crate does not know about yet are skipped
+    pub fn supported_options(&self) -> Result<Vec<Rs2Option>, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let list = rs2_get_options_list(self.options(), &mut error);
+            check_error(error)?;
+
+            let size = rs2_get_options_list_size(list, &mut error);
+            if let Err(err) = check_error(error) {
+                rs2_delete_options_list(list);
+                return Err(err);
+            }
+
+            let mut options = Vec::with_capacity(size as usize);
+            for i in 0..size {
+                let option = rs2_get_option_from_list(list, i, &mut error);
+                if let Err(err) = check_error(error) {
+                    rs2_delete_options_list(list);
+                    return Err(err);
+                }
+
+                match Rs2Option::from_u32(option) {
+                    Some(option) => options.push(option),
+                    None => log::debug!("Skipping unknown option {}", option),
+                }
+            }
+
+            rs2_delete_options_list(list);
+            rs2_free_error(error);
+            return Ok(options);
+        }
+    }
+
+    //What the option does, as described by the device
+    pub fn option_description(&self, option: Rs2Option) -> Result<String, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let description = rs2_get_option_description(self.options(), option as u32, &mut error);
+            check_error(error)?;
+
+            rs2_free_error(error);
+            return Ok(CStr::from_ptr(description).to_string_lossy().into_owned());
+        }
+    }
+
+    //Name of a specific value for enum like options, e.g. the visual preset names. None for plain numbers
+    pub fn option_value_description(
+        &self,
+        option: Rs2Option,
+        value: f32,
+    ) -> Result<Option<String>, RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            let description =
+                rs2_get_option_value_description(self.options(), option as u32, value, &mut error);
+            check_error(error)?;
+
+            rs2_free_error(error);
+
+            if description.is_null() {
+                return Ok(None);
+            }
+            return Ok(Some(
+                CStr::from_ptr(description).to_string_lossy().into_owned(),
+            ));
+        }
+    }
+
+    //Returns the preset the sensor reports afterwards, which is None when that value is not one of P's presets.
+    //Changing most other options afterwards flips the sensor back to the custom preset.
+    //Fails without touching the sensor when P is meant for another camera family
+    pub fn apply_visual_preset<P: VisualPreset>(
+        &self,
+        preset: P,
+    ) -> Result<Option<P>, RealsenseError> {
+        //The preset values of different families overlap, so a mismatch would silently pick something else
+        let product_line = self.info(Rs2CameraInfo::ProductLine)?;
+        if product_line.as_deref() != Some(P::PRODUCT_LINE) {
+            return Err(RealsenseError::PresetProductLineMismatch {
+                expected: P::PRODUCT_LINE,
+                product_line,
+            });
+        }
+
+        //Every preset enum is repr(u32), so this never fails
+        let value = preset.to_f32().unwrap();
+        self.set_option(Rs2Option::VisualPreset, value)?;
+
+        let applied = self.get_option(Rs2Option::VisualPreset)?;
+        return Ok(P::from_f32(applied));
+    }
+}
+
+impl Drop for Sensor {
+    fn drop(&mut self) {
+        unsafe {
+            rs2_delete_sensor(self.sensor);
+        }
+    }
+}
@@ -0,0 +1,126 @@
+use crate::{
+    option::{OptionRange, Rs2Option},
+    RealsenseError,
+};
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, fmt};
+
+//Every writable option of every sensor on a device. Plain data so it can be written out,
+//diffed and read back on another machine without a camera attached
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct OptionsSnapshot {
+    pub sensors: Vec<SensorSnapshot>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct SensorSnapshot {
+    //Sensors are matched by name when the snapshot gets applied
+    pub name: String,
+    pub options: BTreeMap<Rs2Option, f32>,
+}
+
+impl OptionsSnapshot {
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+
+    pub fn sensor(&self, name: &str) -> Option<&SensorSnapshot> {
+        self.sensors.iter().find(|sensor| sensor.name == name)
+    }
+}
+
+#[derive(Debug)]
+pub enum OptionFailureReason {
+    //The device has no sensor with the snapshot's sensor name
+    SensorMissing,
+    Unsupported,
+    ReadOnly,
+    OutOfRange(OptionRange),
+    Failed(RealsenseError),
+}
+
+#[derive(Debug)]
+pub struct OptionFailure {
+    pub sensor: String,
+    pub option: Rs2Option,
+    pub value: f32,
+    pub reason: OptionFailureReason,
+}
+
+impl fmt::Display for OptionFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Could not set {} on {} to {}: ",
+            self.option, self.sensor, self.value
+        )?;
+
+        match &self.reason {
+            OptionFailureReason::SensorMissing => write!(f, "the device has no such sensor"),
+            OptionFailureReason::Unsupported => write!(f, "the sensor does not support it"),
+            OptionFailureReason::ReadOnly => write!(f, "it is read only"),
+            OptionFailureReason::OutOfRange(range) => {
+                write!(f, "outside of {} to {}", range.min, range.max)
+            }
+            OptionFailureReason::Failed(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn snapshot() -> OptionsSnapshot {
+        OptionsSnapshot {
+            sensors: vec![
+                SensorSnapshot {
+                    name: "Stereo Module".to_string(),
+                    options: BTreeMap::from([
+                        (Rs2Option::Exposure, 8500.0),
+                        (Rs2Option::LaserPower, 150.0),
+                        (Rs2Option::VisualPreset, 1.0),
+                    ]),
+                },
+                SensorSnapshot {
+                    name: "RGB Camera".to_string(),
+                    options: BTreeMap::from([(Rs2Option::Gain, 64.0)]),
+                },
+            ],
+        }
+    }
+
+    #[test]
+    fn json_round_trip() {
+        let snapshot = snapshot();
+
+        let json = snapshot.to_json().unwrap();
+        assert_eq!(OptionsSnapshot::from_json(&json).unwrap(), snapshot);
+    }
+
+    #[test]
+    fn unknown_option_is_rejected() {
+        let json = r#"{
+            "sensors": [
+                {
+                    "name": "Stereo Module",
+                    "options": { "Exposure": 8500.0, "NotAnOption": 1.0 }
+                }
+            ]
+        }"#;
+
+        assert!(OptionsSnapshot::from_json(json).is_err());
+
+        //Same document with only known options goes through
+        let known =
+            OptionsSnapshot::from_json(&json.replace(r#", "NotAnOption": 1.0"#, "")).unwrap();
+        assert_eq!(
+            known.sensor("Stereo Module").unwrap().options[&Rs2Option::Exposure],
+            8500.0
+        );
+    }
+}
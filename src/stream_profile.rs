@@ -1,9 +1,463 @@
+use crate::{
+    bindings::*, check_error, distortion::Distortion, format::Rs2Format, stream::Rs2StreamKind,
+    RealsenseError,
+};
+use num_traits::FromPrimitive;
+use std::mem::MaybeUninit;
+
+//Plain description of a stream mode. Calibration is read separately, see PipelineProfile::intrinsics
+//and SafeFrame::intrinsics, since it costs a lot more than the rest and is not there for every mode
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub struct StreamProfile {
-    //stream: Rs2StreamKind,
-    format: Rs2Format,
-    index: i32,
-    unique_id: i32,
-    frame_rate: i32,
-    is_default: bool,
-    should_drop: bool,
+    pub stream: Rs2StreamKind,
+    pub format: Rs2Format,
+    pub index: i32,
+    pub unique_id: i32,
+    pub frame_rate: i32,
+    //Both 0 for streams that are not video, like gyro or accel
+    pub width: i32,
+    pub height: i32,
+    pub is_default: bool,
+}
+
+impl StreamProfile {
+    //The profile is only read, whoever handed it over still owns it
+    pub(crate) unsafe fn new(
+        profile: *const rs2_stream_profile,
+    ) -> Result<StreamProfile, RealsenseError> {
+        let mut error = std::ptr::null_mut::<rs2_error>();
+
+        let data = ProfileData::new(profile)?;
+
+        let is_default = rs2_is_stream_profile_default(profile, &mut error);
+        check_error(error)?;
+
+        let is_video = rs2_stream_profile_is(
+            profile,
+            rs2_extension_RS2_EXTENSION_VIDEO_PROFILE,
+            &mut error,
+        );
+        check_error(error)?;
+
+        let mut width = 0;
+        let mut height = 0;
+        if is_video != 0 {
+            rs2_get_video_stream_resolution(profile, &mut width, &mut height, &mut error);
+            check_error(error)?;
+        }
+
+        rs2_free_error(error);
+
+        return Ok(StreamProfile {
+            stream: data.stream,
+            format: data.format,
+            index: data.index,
+            unique_id: data.unique_id,
+            frame_rate: data.frame_rate,
+            width,
+            height,
+            is_default: is_default != 0,
+        });
+    }
+}
+
+//Everything a single rs2_get_stream_profile_data call reports, cheap enough to read for every frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) struct ProfileData {
+    pub stream: Rs2StreamKind,
+    pub format: Rs2Format,
+    pub index: i32,
+    pub unique_id: i32,
+    pub frame_rate: i32,
+}
+
+impl ProfileData {
+    pub(crate) unsafe fn new(
+        profile: *const rs2_stream_profile,
+    ) -> Result<ProfileData, RealsenseError> {
+        let mut error = std::ptr::null_mut::<rs2_error>();
+
+        let mut stream = MaybeUninit::uninit();
+        let mut format = MaybeUninit::uninit();
+        let mut index = MaybeUninit::uninit();
+        let mut unique_id = MaybeUninit::uninit();
+        let mut frame_rate = MaybeUninit::uninit();
+
+        rs2_get_stream_profile_data(
+            profile,
+            stream.as_mut_ptr(),
+            format.as_mut_ptr(),
+            index.as_mut_ptr(),
+            unique_id.as_mut_ptr(),
+            frame_rate.as_mut_ptr(),
+            &mut error,
+        );
+        check_error(error)?;
+
+        rs2_free_error(error);
+
+        return Ok(ProfileData {
+            stream: Rs2StreamKind::from_u32(stream.assume_init()).unwrap(),
+            format: Rs2Format::from_i32(format.assume_init() as i32).unwrap(),
+            index: index.assume_init(),
+            unique_id: unique_id.assume_init(),
+            frame_rate: frame_rate.assume_init(),
+        });
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Intrinsics {
+    pub width: i32,
+    pub height: i32,
+    //Principal point, as a pixel offset from the top left corner
+    pub ppx: f32,
+    pub ppy: f32,
+    //Focal length as a multiple of pixel width and height
+    pub fx: f32,
+    pub fy: f32,
+    pub model: Distortion,
+    //[k1, k2, p1, p2, k3] for Brown-Conrady, [k1, k2, k3, k4, 0] for F-Theta
+    pub coeffs: [f32; 5],
+}
+
+impl Intrinsics {
+    //Not every video mode is calibrated, those come back as None instead of an error
+    pub(crate) unsafe fn new(
+        profile: *const rs2_stream_profile,
+    ) -> Result<Option<Intrinsics>, RealsenseError> {
+        let mut error = std::ptr::null_mut::<rs2_error>();
+        let mut intrinsics = MaybeUninit::<rs2_intrinsics>::uninit();
+
+        rs2_get_video_stream_intrinsics(profile, intrinsics.as_mut_ptr(), &mut error);
+        if let Err(err) = check_error(error) {
+            log::debug!("No intrinsics for the stream profile: {}", err);
+            rs2_free_error(error);
+            return Ok(None);
+        }
+
+        rs2_free_error(error);
+
+        let intrinsics = intrinsics.assume_init();
+        return Intrinsics::try_from(intrinsics).map(Some);
+    }
+}
+
+//Fails for distortion models this crate does not know, projecting those as undistorted would be silently wrong
+impl TryFrom<rs2_intrinsics> for Intrinsics {
+    type Error = RealsenseError;
+
+    fn try_from(intrinsics: rs2_intrinsics) -> Result<Self, Self::Error> {
+        let model = Distortion::from_u32(intrinsics.model)
+            .ok_or(RealsenseError::UnknownDistortion(intrinsics.model))?;
+
+        return Ok(Intrinsics {
+            width: intrinsics.width,
+            height: intrinsics.height,
+            ppx: intrinsics.ppx,
+            ppy: intrinsics.ppy,
+            fx: intrinsics.fx,
+            fy: intrinsics.fy,
+            model,
+            coeffs: intrinsics.coeffs,
+        });
+    }
+}
+
+impl From<Intrinsics> for rs2_intrinsics {
+    fn from(intrinsics: Intrinsics) -> Self {
+        rs2_intrinsics {
+            width: intrinsics.width,
+            height: intrinsics.height,
+            ppx: intrinsics.ppx,
+            ppy: intrinsics.ppy,
+            fx: intrinsics.fx,
+            fy: intrinsics.fy,
+            model: intrinsics.model as u32,
+            coeffs: intrinsics.coeffs,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MotionIntrinsics {
+    //Scale and bias per axis, a corrected sample is data * [x, y, z, 1]
+    pub data: [[f32; 4]; 3],
+    pub noise_variances: [f32; 3],
+    pub bias_variances: [f32; 3],
+}
+
+impl MotionIntrinsics {
+    //Not every motion module is calibrated, those come back as None instead of an error
+    pub(crate) unsafe fn new(profile: *const rs2_stream_profile) -> Option<MotionIntrinsics> {
+        let mut error = std::ptr::null_mut::<rs2_error>();
+        let mut intrinsics = MaybeUninit::<rs2_motion_device_intrinsic>::uninit();
+
+        rs2_get_motion_intrinsics(profile, intrinsics.as_mut_ptr(), &mut error);
+        if let Err(err) = check_error(error) {
+            log::debug!("No motion intrinsics for the stream profile: {}", err);
+            rs2_free_error(error);
+            return None;
+        }
+
+        rs2_free_error(error);
+
+        let intrinsics = intrinsics.assume_init();
+        return Some(MotionIntrinsics {
+            data: intrinsics.data,
+            noise_variances: intrinsics.noise_variances,
+            bias_variances: intrinsics.bias_variances,
+        });
+    }
+}
+
+//Rigid transform from one stream's 3D coordinates to another's
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Extrinsics {
+    //Column major 3x3 rotation matrix
+    pub rotation: [f32; 9],
+    //In meters
+    pub translation: [f32; 3],
+}
+
+impl Extrinsics {
+    pub(crate) unsafe fn new(
+        from: *const rs2_stream_profile,
+        to: *const rs2_stream_profile,
+    ) -> Result<Extrinsics, RealsenseError> {
+        let mut error = std::ptr::null_mut::<rs2_error>();
+        let mut extrinsics = MaybeUninit::<rs2_extrinsics>::uninit();
+
+        rs2_get_extrinsics(from, to, extrinsics.as_mut_ptr(), &mut error);
+        check_error(error)?;
+
+        rs2_free_error(error);
+        return Ok(Extrinsics::from(extrinsics.assume_init()));
+    }
+}
+
+impl From<rs2_extrinsics> for Extrinsics {
+    fn from(extrinsics: rs2_extrinsics) -> Self {
+        Extrinsics {
+            rotation: extrinsics.rotation,
+            translation: extrinsics.translation,
+        }
+    }
+}
+
+impl From<Extrinsics> for rs2_extrinsics {
+    fn from(extrinsics: Extrinsics) -> Self {
+        rs2_extrinsics {
+            rotation: extrinsics.rotation,
+            translation: extrinsics.translation,
+        }
+    }
+}
+
+//Reads every profile in the list, the list itself is left for the caller to delete
+pub(crate) unsafe fn profiles_in_list(
+    list: *const rs2_stream_profile_list,
+) -> Result<Vec<StreamProfile>, RealsenseError> {
+    let mut error = std::ptr::null_mut::<rs2_error>();
+
+    let count = rs2_get_stream_profiles_count(list, &mut error);
+    check_error(error)?;
+
+    let mut profiles = Vec::with_capacity(count as usize);
+
+    for i in 0..count {
+        let profile = rs2_get_stream_profile(list, i, &mut error);
+        check_error(error)?;
+
+        profiles.push(StreamProfile::new(profile)?);
+    }
+
+    rs2_free_error(error);
+    return Ok(profiles);
+}
+
+//Every field left as None matches anything. Rs2Format::Any and zero sizes or fps
+//count as None too, same as they do for PipelineConfig::enable_stream
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct StreamProfileQuery {
+    pub stream: Option<Rs2StreamKind>,
+    pub index: Option<i32>,
+    pub format: Option<Rs2Format>,
+    pub width: Option<i32>,
+    pub height: Option<i32>,
+    pub frame_rate: Option<i32>,
+}
+
+impl StreamProfileQuery {
+    pub fn new() -> Self {
+        StreamProfileQuery::default()
+    }
+
+    pub fn stream(mut self, stream: Rs2StreamKind) -> Self {
+        self.stream = Some(stream).filter(|stream| *stream != Rs2StreamKind::Any);
+        self
+    }
+
+    pub fn index(mut self, index: i32) -> Self {
+        self.index = Some(index);
+        self
+    }
+
+    pub fn format(mut self, format: Rs2Format) -> Self {
+        self.format = Some(format).filter(|format| *format != Rs2Format::Any);
+        self
+    }
+
+    pub fn resolution(mut self, width: i32, height: i32) -> Self {
+        self.width = Some(width).filter(|width| *width > 0);
+        self.height = Some(height).filter(|height| *height > 0);
+        self
+    }
+
+    pub fn frame_rate(mut self, frame_rate: i32) -> Self {
+        self.frame_rate = Some(frame_rate).filter(|frame_rate| *frame_rate > 0);
+        self
+    }
+
+    pub fn matches(&self, profile: &StreamProfile) -> bool {
+        self.matches_stream(profile)
+            && self.width.is_none_or(|width| width == profile.width)
+            && self.height.is_none_or(|height| height == profile.height)
+            && self
+                .frame_rate
+                .is_none_or(|frame_rate| frame_rate == profile.frame_rate)
+    }
+
+    pub fn filter(&self, profiles: &[StreamProfile]) -> Vec<StreamProfile> {
+        profiles
+            .iter()
+            .filter(|profile| self.matches(profile))
+            .copied()
+            .collect()
+    }
+
+    //Stream, index and format have to match exactly since there is no converting between them.
+    //Out of what is left, picks the smallest relative difference in width, height and fps,
+    //preferring the device's default profiles on ties
+    pub fn closest(&self, profiles: &[StreamProfile]) -> Option<StreamProfile> {
+        profiles
+            .iter()
+            .filter(|profile| self.matches_stream(profile))
+            .min_by(|a, b| {
+                self.distance(a)
+                    .total_cmp(&self.distance(b))
+                    .then(b.is_default.cmp(&a.is_default))
+            })
+            .copied()
+    }
+
+    fn matches_stream(&self, profile: &StreamProfile) -> bool {
+        self.stream.is_none_or(|stream| stream == profile.stream)
+            && self.index.is_none_or(|index| index == profile.index)
+            && self.format.is_none_or(|format| format == profile.format)
+    }
+
+    fn distance(&self, profile: &StreamProfile) -> f64 {
+        let relative = |wanted: Option<i32>, actual: i32| {
+            wanted.map_or(0.0, |wanted| (actual - wanted).abs() as f64 / wanted as f64)
+        };
+
+        relative(self.width, profile.width)
+            + relative(self.height, profile.height)
+            + relative(self.frame_rate, profile.frame_rate)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile(
+        stream: Rs2StreamKind,
+        format: Rs2Format,
+        width: i32,
+        height: i32,
+        frame_rate: i32,
+        is_default: bool,
+    ) -> StreamProfile {
+        StreamProfile {
+            stream,
+            format,
+            index: 0,
+            unique_id: 0,
+            frame_rate,
+            width,
+            height,
+            is_default,
+        }
+    }
+
+    fn profiles() -> Vec<StreamProfile> {
+        vec![
+            profile(Rs2StreamKind::Depth, Rs2Format::Z16, 640, 480, 30, false),
+            profile(Rs2StreamKind::Depth, Rs2Format::Z16, 848, 480, 90, false),
+            profile(Rs2StreamKind::Depth, Rs2Format::Z16, 1280, 720, 30, true),
+            profile(Rs2StreamKind::Color, Rs2Format::RGB8, 640, 480, 30, false),
+            profile(Rs2StreamKind::Color, Rs2Format::RGB8, 1280, 720, 30, true),
+        ]
+    }
+
+    #[test]
+    fn exact_match() {
+        let query = StreamProfileQuery::new()
+            .stream(Rs2StreamKind::Depth)
+            .format(Rs2Format::Z16)
+            .resolution(848, 480)
+            .frame_rate(90);
+
+        assert_eq!(query.filter(&profiles()), vec![profiles()[1]]);
+        assert_eq!(query.closest(&profiles()), Some(profiles()[1]));
+    }
+
+    #[test]
+    fn any_and_zero_are_wildcards() {
+        let query = StreamProfileQuery::new()
+            .stream(Rs2StreamKind::Any)
+            .format(Rs2Format::Any)
+            .resolution(0, 0)
+            .frame_rate(0);
+
+        assert_eq!(query, StreamProfileQuery::new());
+        assert_eq!(query.filter(&profiles()), profiles());
+
+        let color = StreamProfileQuery::new()
+            .stream(Rs2StreamKind::Color)
+            .resolution(0, 0);
+        assert_eq!(color.filter(&profiles()), profiles()[3..].to_vec());
+    }
+
+    #[test]
+    fn closest_prefers_default_on_ties() {
+        //640x480 and 1280x720 are both a third off in width and a fifth in height, only the second is default
+        let query = StreamProfileQuery::new()
+            .stream(Rs2StreamKind::Color)
+            .resolution(960, 600)
+            .frame_rate(30);
+        assert_eq!(query.closest(&profiles()), Some(profiles()[4]));
+
+        let query = StreamProfileQuery::new()
+            .stream(Rs2StreamKind::Depth)
+            .resolution(850, 480)
+            .frame_rate(60);
+        assert_eq!(query.closest(&profiles()), Some(profiles()[1]));
+    }
+
+    #[test]
+    fn no_match() {
+        let query = StreamProfileQuery::new()
+            .stream(Rs2StreamKind::Depth)
+            .resolution(320, 240);
+        assert!(query.filter(&profiles()).is_empty());
+
+        //closest never swaps the stream or format
+        let query = StreamProfileQuery::new()
+            .stream(Rs2StreamKind::Color)
+            .format(Rs2Format::Z16);
+        assert_eq!(query.closest(&profiles()), None);
+    }
 }
@@ -0,0 +1,451 @@
+use crate::{
+    bindings::*, camera_info::Rs2CameraInfo, check_error, Device, DeviceEvent, Frameset,
+    RealsenseError, RealsenseInstance,
+};
+use std::{
+    sync::mpsc,
+    thread,
+    time::{Duration, Instant},
+};
+
+//How often wait_for_device looks for the camera once it is known to be connected again
+const DEVICE_POLL_INTERVAL: Duration = Duration::from_millis(100);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RecoveryPolicy {
+    //How many failed waits in a row it takes to consider the pipeline dead
+    pub max_consecutive_timeouts: u32,
+    pub frame_timeout_ms: u32,
+    //How long a recovery waits for the camera to come back. Once it passes next_frame fails
+    //with DeviceNotFound and the following call starts waiting again
+    pub device_timeout_ms: u32,
+    //Power cycle the camera with rs2_hardware_reset before waiting for it to come back
+    pub hardware_reset: bool,
+}
+
+impl Default for RecoveryPolicy {
+    fn default() -> Self {
+        RecoveryPolicy {
+            max_consecutive_timeouts: 3,
+            frame_timeout_ms: 5000,
+            device_timeout_ms: 30000,
+            hardware_reset: false,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SupervisorState {
+    Streaming,
+    Recovering,
+}
+
+//Everything the supervisor needs from a camera. RealsenseSource is the real one,
+//the trait is there so the reconnect logic can be driven by a mock without hardware
+pub trait FrameSource {
+    type Frame;
+
+    fn wait_for_frames(&mut self, timeout_ms: u32) -> Result<Self::Frame, RealsenseError>;
+
+    //True while the camera is unplugged
+    fn device_removed(&mut self) -> bool;
+
+    fn hardware_reset(&mut self) -> Result<(), RealsenseError>;
+
+    //Blocks until the same camera is connected again, or fails once timeout_ms has passed
+    fn wait_for_device(&mut self, timeout_ms: u32) -> Result<(), RealsenseError>;
+
+    //Starts streaming again with the configuration used before the failure
+    fn restart(&mut self) -> Result<(), RealsenseError>;
+}
+
+pub struct Supervisor<S: FrameSource> {
+    source: S,
+    policy: RecoveryPolicy,
+    state: SupervisorState,
+    consecutive_timeouts: u32,
+    recoveries: u32,
+}
+
+impl<S: FrameSource> Supervisor<S> {
+    pub fn new(source: S, policy: RecoveryPolicy) -> Self {
+        Supervisor {
+            source,
+            policy,
+            state: SupervisorState::Streaming,
+            consecutive_timeouts: 0,
+            recoveries: 0,
+        }
+    }
+
+    pub fn state(&self) -> SupervisorState {
+        self.state
+    }
+
+    //Number of times the pipeline was brought back up
+    pub fn recoveries(&self) -> u32 {
+        self.recoveries
+    }
+
+    pub fn source(&self) -> &S {
+        &self.source
+    }
+
+    pub fn source_mut(&mut self) -> &mut S {
+        &mut self.source
+    }
+
+    pub fn into_inner(self) -> S {
+        self.source
+    }
+
+    //A single failed wait is handed back to the caller as is. Once the policy decides the
+    //pipeline is dead, the next call blocks until the camera is back and streaming again
+    pub fn next_frame(&mut self) -> Result<S::Frame, RealsenseError> {
+        if self.state == SupervisorState::Streaming && self.source.device_removed() {
+            log::warn!("Device was removed, waiting for it to come back");
+            self.state = SupervisorState::Recovering;
+        }
+
+        if self.state == SupervisorState::Recovering {
+            self.recover()?;
+        }
+
+        match self.source.wait_for_frames(self.policy.frame_timeout_ms) {
+            Ok(frame) => {
+                self.consecutive_timeouts = 0;
+                return Ok(frame);
+            }
+            Err(err) => {
+                self.consecutive_timeouts += 1;
+                log::warn!(
+                    "Waiting for frames failed {} times in a row: {}",
+                    self.consecutive_timeouts,
+                    err
+                );
+
+                if self.consecutive_timeouts >= self.policy.max_consecutive_timeouts
+                    || self.source.device_removed()
+                {
+                    self.state = SupervisorState::Recovering;
+                }
+                return Err(err);
+            }
+        }
+    }
+
+    //Stays in Recovering if any step fails so the next call tries again
+    fn recover(&mut self) -> Result<(), RealsenseError> {
+        //There is nothing to reset while the camera is unplugged
+        if self.policy.hardware_reset && !self.source.device_removed() {
+            if let Err(err) = self.source.hardware_reset() {
+                log::warn!("Hardware reset failed: {}", err);
+            }
+        }
+
+        self.source.wait_for_device(self.policy.device_timeout_ms)?;
+        self.source.restart()?;
+
+        self.state = SupervisorState::Streaming;
+        self.consecutive_timeouts = 0;
+        self.recoveries += 1;
+        log::info!("Pipeline recovered, {} recoveries so far", self.recoveries);
+
+        return Ok(());
+    }
+}
+
+pub struct RealsenseSource {
+    instance: RealsenseInstance,
+    serial: String,
+    events: mpsc::Receiver<DeviceEvent>,
+    removed: bool,
+}
+
+impl RealsenseSource {
+    //Takes over the context's devices changed callback, see RealsenseInstance::supervise
+    pub fn new(mut instance: RealsenseInstance) -> Result<Self, RealsenseError> {
+        let serial = instance
+            .device
+            .info(Rs2CameraInfo::SerialNumber)?
+            .ok_or(RealsenseError::NoDevice)?;
+        let events = instance.context.device_events()?;
+
+        return Ok(RealsenseSource {
+            instance,
+            serial,
+            events,
+            removed: false,
+        });
+    }
+
+    pub fn instance(&self) -> &RealsenseInstance {
+        &self.instance
+    }
+
+    pub fn instance_mut(&mut self) -> &mut RealsenseInstance {
+        &mut self.instance
+    }
+
+    //Only ours, other cameras connected at the same time are skipped
+    fn find_device(&self) -> Result<Option<Device>, RealsenseError> {
+        let context = &self.instance.context;
+
+        for info in context.devices()? {
+            if info.serial_number.as_ref() != Some(&self.serial) {
+                continue;
+            }
+
+            //Indexes shift when devices come and go, so check the serial of what actually got opened
+            let device = context.open_device(&info)?;
+            if device.info(Rs2CameraInfo::SerialNumber)?.as_ref() == Some(&self.serial) {
+                return Ok(Some(device));
+            }
+        }
+
+        return Ok(None);
+    }
+
+    fn not_found(&self) -> RealsenseError {
+        RealsenseError::DeviceNotFound {
+            serial: self.serial.clone(),
+        }
+    }
+
+    fn handle_event(&mut self, event: DeviceEvent) {
+        match event {
+            DeviceEvent::Removed(info) if info.serial_number.as_ref() == Some(&self.serial) => {
+                self.removed = true;
+            }
+            DeviceEvent::Added(info) if info.serial_number.as_ref() == Some(&self.serial) => {
+                self.removed = false;
+            }
+            _ => {}
+        }
+    }
+}
+
+impl FrameSource for RealsenseSource {
+    type Frame = Frameset;
+
+    fn wait_for_frames(&mut self, timeout_ms: u32) -> Result<Frameset, RealsenseError> {
+        self.instance.wait_for_frames(timeout_ms)
+    }
+
+    fn device_removed(&mut self) -> bool {
+        while let Ok(event) = self.events.try_recv() {
+            self.handle_event(event);
+        }
+        self.removed
+    }
+
+    fn hardware_reset(&mut self) -> Result<(), RealsenseError> {
+        unsafe {
+            let mut error = std::ptr::null_mut::<rs2_error>();
+
+            rs2_hardware_reset(self.instance.device.device, &mut error);
+            check_error(error)?;
+
+            rs2_free_error(error);
+        }
+
+        //The camera drops off the bus after a reset, wait_for_device has to see it come back
+        self.removed = true;
+        return Ok(());
+    }
+
+    fn wait_for_device(&mut self, timeout_ms: u32) -> Result<(), RealsenseError> {
+        let deadline = Instant::now() + Duration::from_millis(timeout_ms as u64);
+
+        //Events arrive in order, so block until the arrival matching the last removal
+        while self.removed {
+            let remaining = deadline.saturating_duration_since(Instant::now());
+            match self.events.recv_timeout(remaining) {
+                Ok(event) => self.handle_event(event),
+                Err(mpsc::RecvTimeoutError::Timeout) => return Err(self.not_found()),
+                //Nothing left to tell us about arrivals, polling below finds the camera anyway
+                Err(mpsc::RecvTimeoutError::Disconnected) => break,
+            }
+        }
+
+        //The arrival event can come before the device is ready to be opened
+        loop {
+            if let Some(device) = self.find_device()? {
+                self.instance.device = device;
+                self.removed = false;
+                return Ok(());
+            }
+
+            if Instant::now() >= deadline {
+                return Err(self.not_found());
+            }
+            thread::sleep(DEVICE_POLL_INTERVAL);
+        }
+    }
+
+    fn restart(&mut self) -> Result<(), RealsenseError> {
+        self.instance.restart()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::VecDeque;
+
+    //Plays back scripted results and records every call the supervisor makes
+    #[derive(Default)]
+    struct MockSource {
+        frames: VecDeque<Result<u32, RealsenseError>>,
+        removed: VecDeque<bool>,
+        restarts: VecDeque<Result<(), RealsenseError>>,
+        devices: VecDeque<Result<(), RealsenseError>>,
+        calls: Vec<&'static str>,
+    }
+
+    impl FrameSource for MockSource {
+        type Frame = u32;
+
+        fn wait_for_frames(&mut self, _timeout_ms: u32) -> Result<u32, RealsenseError> {
+            self.calls.push("wait_for_frames");
+            self.frames.pop_front().unwrap_or(Err(timeout()))
+        }
+
+        fn device_removed(&mut self) -> bool {
+            self.removed.pop_front().unwrap_or(false)
+        }
+
+        fn hardware_reset(&mut self) -> Result<(), RealsenseError> {
+            self.calls.push("hardware_reset");
+            Ok(())
+        }
+
+        fn wait_for_device(&mut self, _timeout_ms: u32) -> Result<(), RealsenseError> {
+            self.calls.push("wait_for_device");
+            self.devices.pop_front().unwrap_or(Ok(()))
+        }
+
+        fn restart(&mut self) -> Result<(), RealsenseError> {
+            self.calls.push("restart");
+            self.restarts.pop_front().unwrap_or(Ok(()))
+        }
+    }
+
+    fn timeout() -> RealsenseError {
+        RealsenseError::Api {
+            ty: 0,
+            details: "Frame didn't arrive within 5000".to_string(),
+        }
+    }
+
+    fn supervisor(source: MockSource) -> Supervisor<MockSource> {
+        Supervisor::new(
+            source,
+            RecoveryPolicy {
+                max_consecutive_timeouts: 2,
+                ..RecoveryPolicy::default()
+            },
+        )
+    }
+
+    #[test]
+    fn consecutive_timeouts_start_recovering() {
+        let mut supervisor = supervisor(MockSource {
+            frames: VecDeque::from(vec![Err(timeout()), Ok(1), Err(timeout()), Err(timeout())]),
+            ..MockSource::default()
+        });
+
+        assert!(supervisor.next_frame().is_err());
+        assert_eq!(supervisor.next_frame().unwrap(), 1);
+        assert!(supervisor.next_frame().is_err());
+        assert_eq!(supervisor.state(), SupervisorState::Streaming);
+
+        assert!(supervisor.next_frame().is_err());
+        assert_eq!(supervisor.state(), SupervisorState::Recovering);
+    }
+
+    #[test]
+    fn removed_device_is_waited_for_and_restarted() {
+        let mut supervisor = supervisor(MockSource {
+            frames: VecDeque::from(vec![Ok(1)]),
+            removed: VecDeque::from(vec![true]),
+            ..MockSource::default()
+        });
+
+        assert_eq!(supervisor.next_frame().unwrap(), 1);
+        assert_eq!(
+            supervisor.source().calls,
+            vec!["wait_for_device", "restart", "wait_for_frames"]
+        );
+        assert_eq!(supervisor.state(), SupervisorState::Streaming);
+    }
+
+    #[test]
+    fn failed_restart_stays_recovering() {
+        let mut supervisor = supervisor(MockSource {
+            frames: VecDeque::from(vec![Ok(1)]),
+            removed: VecDeque::from(vec![true]),
+            restarts: VecDeque::from(vec![Err(timeout())]),
+            ..MockSource::default()
+        });
+
+        assert!(supervisor.next_frame().is_err());
+        assert_eq!(supervisor.state(), SupervisorState::Recovering);
+        assert_eq!(supervisor.recoveries(), 0);
+        assert!(!supervisor.source().calls.contains(&"wait_for_frames"));
+
+        //The next call tries again
+        assert_eq!(supervisor.next_frame().unwrap(), 1);
+        assert_eq!(supervisor.state(), SupervisorState::Streaming);
+        assert_eq!(supervisor.recoveries(), 1);
+    }
+
+    #[test]
+    fn device_timeout_stays_recovering() {
+        let mut supervisor = supervisor(MockSource {
+            frames: VecDeque::from(vec![Ok(1)]),
+            removed: VecDeque::from(vec![true]),
+            devices: VecDeque::from(vec![Err(RealsenseError::DeviceNotFound {
+                serial: "012345678901".to_string(),
+            })]),
+            ..MockSource::default()
+        });
+
+        assert!(matches!(
+            supervisor.next_frame(),
+            Err(RealsenseError::DeviceNotFound { .. })
+        ));
+        assert_eq!(supervisor.state(), SupervisorState::Recovering);
+        assert_eq!(supervisor.source().calls, vec!["wait_for_device"]);
+
+        assert_eq!(supervisor.next_frame().unwrap(), 1);
+        assert_eq!(supervisor.recoveries(), 1);
+    }
+
+    #[test]
+    fn recovery_resets_the_timeout_count() {
+        let mut supervisor = supervisor(MockSource {
+            frames: VecDeque::from(vec![
+                Err(timeout()),
+                Err(timeout()),
+                Ok(1),
+                Err(timeout()),
+                Ok(2),
+            ]),
+            ..MockSource::default()
+        });
+
+        assert!(supervisor.next_frame().is_err());
+        assert!(supervisor.next_frame().is_err());
+        assert_eq!(supervisor.state(), SupervisorState::Recovering);
+
+        assert_eq!(supervisor.next_frame().unwrap(), 1);
+        assert_eq!(supervisor.recoveries(), 1);
+
+        //A single timeout after the recovery is not enough to recover again
+        assert!(supervisor.next_frame().is_err());
+        assert_eq!(supervisor.state(), SupervisorState::Streaming);
+        assert_eq!(supervisor.next_frame().unwrap(), 2);
+        assert_eq!(supervisor.recoveries(), 1);
+    }
+}
@@ -0,0 +1,34 @@
+use crate::bindings::*;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+#[repr(u32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rs2CameraInfo {
+    Name = rs2_camera_info_RS2_CAMERA_INFO_NAME,
+
+    SerialNumber = rs2_camera_info_RS2_CAMERA_INFO_SERIAL_NUMBER,
+
+    FirmwareVersion = rs2_camera_info_RS2_CAMERA_INFO_FIRMWARE_VERSION,
+
+    RecommendedFirmwareVersion = rs2_camera_info_RS2_CAMERA_INFO_RECOMMENDED_FIRMWARE_VERSION,
+
+    PhysicalPort = rs2_camera_info_RS2_CAMERA_INFO_PHYSICAL_PORT,
+
+    DebugOpCode = rs2_camera_info_RS2_CAMERA_INFO_DEBUG_OP_CODE,
+
+    AdvancedMode = rs2_camera_info_RS2_CAMERA_INFO_ADVANCED_MODE,
+
+    ProductId = rs2_camera_info_RS2_CAMERA_INFO_PRODUCT_ID,
+
+    CameraLocked = rs2_camera_info_RS2_CAMERA_INFO_CAMERA_LOCKED,
+
+    UsbTypeDescriptor = rs2_camera_info_RS2_CAMERA_INFO_USB_TYPE_DESCRIPTOR,
+
+    ProductLine = rs2_camera_info_RS2_CAMERA_INFO_PRODUCT_LINE,
+
+    AsicSerialNumber = rs2_camera_info_RS2_CAMERA_INFO_ASIC_SERIAL_NUMBER,
+
+    FirmwareUpdateId = rs2_camera_info_RS2_CAMERA_INFO_FIRMWARE_UPDATE_ID,
+
+    IpAddress = rs2_camera_info_RS2_CAMERA_INFO_IP_ADDRESS,
+}
@@ -0,0 +1,28 @@
+use crate::bindings::*;
+use num_derive::{FromPrimitive, ToPrimitive};
+use std::{ffi::CStr, fmt};
+
+//How pixel coordinates map to sensor coordinates, the meaning of Intrinsics::coeffs depends on it
+#[repr(u32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum Distortion {
+    #[default]
+    None = rs2_distortion_RS2_DISTORTION_NONE,
+
+    ModifiedBrownConrady = rs2_distortion_RS2_DISTORTION_MODIFIED_BROWN_CONRADY,
+
+    InverseBrownConrady = rs2_distortion_RS2_DISTORTION_INVERSE_BROWN_CONRADY,
+
+    FTheta = rs2_distortion_RS2_DISTORTION_FTHETA,
+
+    BrownConrady = rs2_distortion_RS2_DISTORTION_BROWN_CONRADY,
+
+    KannalaBrandt4 = rs2_distortion_RS2_DISTORTION_KANNALA_BRANDT4,
+}
+
+impl fmt::Display for Distortion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = unsafe { CStr::from_ptr(rs2_distortion_to_string(*self as u32)) };
+        write!(f, "{}", name.to_string_lossy())
+    }
+}
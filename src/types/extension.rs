@@ -0,0 +1,33 @@
+use crate::bindings::*;
+use num_derive::{FromPrimitive, ToPrimitive};
+
+//The subset of rs2_extension a sensor can be extended to
+#[repr(u32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rs2SensorExtension {
+    Roi = rs2_extension_RS2_EXTENSION_ROI,
+
+    DepthSensor = rs2_extension_RS2_EXTENSION_DEPTH_SENSOR,
+
+    DepthStereoSensor = rs2_extension_RS2_EXTENSION_DEPTH_STEREO_SENSOR,
+
+    SoftwareSensor = rs2_extension_RS2_EXTENSION_SOFTWARE_SENSOR,
+
+    PoseSensor = rs2_extension_RS2_EXTENSION_POSE_SENSOR,
+
+    L500DepthSensor = rs2_extension_RS2_EXTENSION_L500_DEPTH_SENSOR,
+
+    Tm2Sensor = rs2_extension_RS2_EXTENSION_TM2_SENSOR,
+
+    ColorSensor = rs2_extension_RS2_EXTENSION_COLOR_SENSOR,
+
+    MotionSensor = rs2_extension_RS2_EXTENSION_MOTION_SENSOR,
+
+    FisheyeSensor = rs2_extension_RS2_EXTENSION_FISHEYE_SENSOR,
+
+    CalibratedSensor = rs2_extension_RS2_EXTENSION_CALIBRATED_SENSOR,
+
+    MaxUsableRangeSensor = rs2_extension_RS2_EXTENSION_MAX_USABLE_RANGE_SENSOR,
+
+    DebugStreamSensor = rs2_extension_RS2_EXTENSION_DEBUG_STREAM_SENSOR,
+}
@@ -0,0 +1,101 @@
+use crate::bindings::*;
+use num_derive::{FromPrimitive, ToPrimitive};
+use std::{ffi::CStr, fmt};
+
+#[repr(u32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rs2FrameMetadata {
+    FrameCounter = rs2_frame_metadata_value_RS2_FRAME_METADATA_FRAME_COUNTER,
+
+    FrameTimestamp = rs2_frame_metadata_value_RS2_FRAME_METADATA_FRAME_TIMESTAMP,
+
+    SensorTimestamp = rs2_frame_metadata_value_RS2_FRAME_METADATA_SENSOR_TIMESTAMP,
+
+    ActualExposure = rs2_frame_metadata_value_RS2_FRAME_METADATA_ACTUAL_EXPOSURE,
+
+    GainLevel = rs2_frame_metadata_value_RS2_FRAME_METADATA_GAIN_LEVEL,
+
+    AutoExposure = rs2_frame_metadata_value_RS2_FRAME_METADATA_AUTO_EXPOSURE,
+
+    WhiteBalance = rs2_frame_metadata_value_RS2_FRAME_METADATA_WHITE_BALANCE,
+
+    TimeOfArrival = rs2_frame_metadata_value_RS2_FRAME_METADATA_TIME_OF_ARRIVAL,
+
+    Temperature = rs2_frame_metadata_value_RS2_FRAME_METADATA_TEMPERATURE,
+
+    BackendTimestamp = rs2_frame_metadata_value_RS2_FRAME_METADATA_BACKEND_TIMESTAMP,
+
+    ActualFps = rs2_frame_metadata_value_RS2_FRAME_METADATA_ACTUAL_FPS,
+
+    FrameLaserPower = rs2_frame_metadata_value_RS2_FRAME_METADATA_FRAME_LASER_POWER,
+
+    FrameLaserPowerMode = rs2_frame_metadata_value_RS2_FRAME_METADATA_FRAME_LASER_POWER_MODE,
+
+    ExposurePriority = rs2_frame_metadata_value_RS2_FRAME_METADATA_EXPOSURE_PRIORITY,
+
+    ExposureRoiLeft = rs2_frame_metadata_value_RS2_FRAME_METADATA_EXPOSURE_ROI_LEFT,
+
+    ExposureRoiRight = rs2_frame_metadata_value_RS2_FRAME_METADATA_EXPOSURE_ROI_RIGHT,
+
+    ExposureRoiTop = rs2_frame_metadata_value_RS2_FRAME_METADATA_EXPOSURE_ROI_TOP,
+
+    ExposureRoiBottom = rs2_frame_metadata_value_RS2_FRAME_METADATA_EXPOSURE_ROI_BOTTOM,
+
+    Brightness = rs2_frame_metadata_value_RS2_FRAME_METADATA_BRIGHTNESS,
+
+    Contrast = rs2_frame_metadata_value_RS2_FRAME_METADATA_CONTRAST,
+
+    Saturation = rs2_frame_metadata_value_RS2_FRAME_METADATA_SATURATION,
+
+    Sharpness = rs2_frame_metadata_value_RS2_FRAME_METADATA_SHARPNESS,
+
+    AutoWhiteBalanceTemperature =
+        rs2_frame_metadata_value_RS2_FRAME_METADATA_AUTO_WHITE_BALANCE_TEMPERATURE,
+
+    BacklightCompensation = rs2_frame_metadata_value_RS2_FRAME_METADATA_BACKLIGHT_COMPENSATION,
+
+    Hue = rs2_frame_metadata_value_RS2_FRAME_METADATA_HUE,
+
+    Gamma = rs2_frame_metadata_value_RS2_FRAME_METADATA_GAMMA,
+
+    ManualWhiteBalance = rs2_frame_metadata_value_RS2_FRAME_METADATA_MANUAL_WHITE_BALANCE,
+
+    PowerLineFrequency = rs2_frame_metadata_value_RS2_FRAME_METADATA_POWER_LINE_FREQUENCY,
+
+    LowLightCompensation = rs2_frame_metadata_value_RS2_FRAME_METADATA_LOW_LIGHT_COMPENSATION,
+
+    FrameEmitterMode = rs2_frame_metadata_value_RS2_FRAME_METADATA_FRAME_EMITTER_MODE,
+
+    FrameLedPower = rs2_frame_metadata_value_RS2_FRAME_METADATA_FRAME_LED_POWER,
+
+    RawFrameSize = rs2_frame_metadata_value_RS2_FRAME_METADATA_RAW_FRAME_SIZE,
+
+    GpioInputData = rs2_frame_metadata_value_RS2_FRAME_METADATA_GPIO_INPUT_DATA,
+
+    SequenceName = rs2_frame_metadata_value_RS2_FRAME_METADATA_SEQUENCE_NAME,
+
+    SequenceId = rs2_frame_metadata_value_RS2_FRAME_METADATA_SEQUENCE_ID,
+
+    SequenceSize = rs2_frame_metadata_value_RS2_FRAME_METADATA_SEQUENCE_SIZE,
+
+    Trigger = rs2_frame_metadata_value_RS2_FRAME_METADATA_TRIGGER,
+
+    Preset = rs2_frame_metadata_value_RS2_FRAME_METADATA_PRESET,
+
+    InputWidth = rs2_frame_metadata_value_RS2_FRAME_METADATA_INPUT_WIDTH,
+
+    InputHeight = rs2_frame_metadata_value_RS2_FRAME_METADATA_INPUT_HEIGHT,
+
+    SubPresetInfo = rs2_frame_metadata_value_RS2_FRAME_METADATA_SUB_PRESET_INFO,
+
+    CalibInfo = rs2_frame_metadata_value_RS2_FRAME_METADATA_CALIB_INFO,
+
+    Crc = rs2_frame_metadata_value_RS2_FRAME_METADATA_CRC,
+}
+
+impl fmt::Display for Rs2FrameMetadata {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = unsafe { CStr::from_ptr(rs2_frame_metadata_to_string(*self as u32)) };
+        write!(f, "{}", name.to_string_lossy())
+    }
+}
@@ -1,2 +1,8 @@
+pub mod camera_info;
+pub mod distortion;
+pub mod extension;
 pub mod format;
+pub mod frame_metadata;
+pub mod option;
 pub mod stream;
+pub mod visual_preset;
@@ -0,0 +1,231 @@
+use crate::bindings::*;
+use num_derive::{FromPrimitive, ToPrimitive};
+use serde::{Deserialize, Serialize};
+use std::{ffi::CStr, fmt};
+
+#[repr(u32)]
+#[derive(
+    FromPrimitive,
+    ToPrimitive,
+    Debug,
+    Clone,
+    Copy,
+    PartialEq,
+    Eq,
+    Hash,
+    PartialOrd,
+    Ord,
+    Serialize,
+    Deserialize,
+)]
+pub enum Rs2Option {
+    BacklightCompensation = rs2_option_RS2_OPTION_BACKLIGHT_COMPENSATION,
+
+    Brightness = rs2_option_RS2_OPTION_BRIGHTNESS,
+
+    Contrast = rs2_option_RS2_OPTION_CONTRAST,
+
+    Exposure = rs2_option_RS2_OPTION_EXPOSURE,
+
+    Gain = rs2_option_RS2_OPTION_GAIN,
+
+    Gamma = rs2_option_RS2_OPTION_GAMMA,
+
+    Hue = rs2_option_RS2_OPTION_HUE,
+
+    Saturation = rs2_option_RS2_OPTION_SATURATION,
+
+    Sharpness = rs2_option_RS2_OPTION_SHARPNESS,
+
+    WhiteBalance = rs2_option_RS2_OPTION_WHITE_BALANCE,
+
+    EnableAutoExposure = rs2_option_RS2_OPTION_ENABLE_AUTO_EXPOSURE,
+
+    EnableAutoWhiteBalance = rs2_option_RS2_OPTION_ENABLE_AUTO_WHITE_BALANCE,
+
+    VisualPreset = rs2_option_RS2_OPTION_VISUAL_PRESET,
+
+    LaserPower = rs2_option_RS2_OPTION_LASER_POWER,
+
+    Accuracy = rs2_option_RS2_OPTION_ACCURACY,
+
+    MotionRange = rs2_option_RS2_OPTION_MOTION_RANGE,
+
+    FilterOption = rs2_option_RS2_OPTION_FILTER_OPTION,
+
+    ConfidenceThreshold = rs2_option_RS2_OPTION_CONFIDENCE_THRESHOLD,
+
+    EmitterEnabled = rs2_option_RS2_OPTION_EMITTER_ENABLED,
+
+    FramesQueueSize = rs2_option_RS2_OPTION_FRAMES_QUEUE_SIZE,
+
+    TotalFrameDrops = rs2_option_RS2_OPTION_TOTAL_FRAME_DROPS,
+
+    AutoExposureMode = rs2_option_RS2_OPTION_AUTO_EXPOSURE_MODE,
+
+    PowerLineFrequency = rs2_option_RS2_OPTION_POWER_LINE_FREQUENCY,
+
+    AsicTemperature = rs2_option_RS2_OPTION_ASIC_TEMPERATURE,
+
+    ErrorPollingEnabled = rs2_option_RS2_OPTION_ERROR_POLLING_ENABLED,
+
+    ProjectorTemperature = rs2_option_RS2_OPTION_PROJECTOR_TEMPERATURE,
+
+    OutputTriggerEnabled = rs2_option_RS2_OPTION_OUTPUT_TRIGGER_ENABLED,
+
+    MotionModuleTemperature = rs2_option_RS2_OPTION_MOTION_MODULE_TEMPERATURE,
+
+    DepthUnits = rs2_option_RS2_OPTION_DEPTH_UNITS,
+
+    EnableMotionCorrection = rs2_option_RS2_OPTION_ENABLE_MOTION_CORRECTION,
+
+    AutoExposurePriority = rs2_option_RS2_OPTION_AUTO_EXPOSURE_PRIORITY,
+
+    ColorScheme = rs2_option_RS2_OPTION_COLOR_SCHEME,
+
+    HistogramEqualizationEnabled = rs2_option_RS2_OPTION_HISTOGRAM_EQUALIZATION_ENABLED,
+
+    MinDistance = rs2_option_RS2_OPTION_MIN_DISTANCE,
+
+    MaxDistance = rs2_option_RS2_OPTION_MAX_DISTANCE,
+
+    TextureSource = rs2_option_RS2_OPTION_TEXTURE_SOURCE,
+
+    FilterMagnitude = rs2_option_RS2_OPTION_FILTER_MAGNITUDE,
+
+    FilterSmoothAlpha = rs2_option_RS2_OPTION_FILTER_SMOOTH_ALPHA,
+
+    FilterSmoothDelta = rs2_option_RS2_OPTION_FILTER_SMOOTH_DELTA,
+
+    HolesFill = rs2_option_RS2_OPTION_HOLES_FILL,
+
+    StereoBaseline = rs2_option_RS2_OPTION_STEREO_BASELINE,
+
+    AutoExposureConvergeStep = rs2_option_RS2_OPTION_AUTO_EXPOSURE_CONVERGE_STEP,
+
+    InterCamSyncMode = rs2_option_RS2_OPTION_INTER_CAM_SYNC_MODE,
+
+    StreamFilter = rs2_option_RS2_OPTION_STREAM_FILTER,
+
+    StreamFormatFilter = rs2_option_RS2_OPTION_STREAM_FORMAT_FILTER,
+
+    StreamIndexFilter = rs2_option_RS2_OPTION_STREAM_INDEX_FILTER,
+
+    EmitterOnOff = rs2_option_RS2_OPTION_EMITTER_ON_OFF,
+
+    ZeroOrderPointX = rs2_option_RS2_OPTION_ZERO_ORDER_POINT_X,
+
+    ZeroOrderPointY = rs2_option_RS2_OPTION_ZERO_ORDER_POINT_Y,
+
+    LldTemperature = rs2_option_RS2_OPTION_LLD_TEMPERATURE,
+
+    McTemperature = rs2_option_RS2_OPTION_MC_TEMPERATURE,
+
+    MaTemperature = rs2_option_RS2_OPTION_MA_TEMPERATURE,
+
+    HardwarePreset = rs2_option_RS2_OPTION_HARDWARE_PRESET,
+
+    GlobalTimeEnabled = rs2_option_RS2_OPTION_GLOBAL_TIME_ENABLED,
+
+    ApdTemperature = rs2_option_RS2_OPTION_APD_TEMPERATURE,
+
+    EnableMapping = rs2_option_RS2_OPTION_ENABLE_MAPPING,
+
+    EnableRelocalization = rs2_option_RS2_OPTION_ENABLE_RELOCALIZATION,
+
+    EnablePoseJumping = rs2_option_RS2_OPTION_ENABLE_POSE_JUMPING,
+
+    EnableDynamicCalibration = rs2_option_RS2_OPTION_ENABLE_DYNAMIC_CALIBRATION,
+
+    DepthOffset = rs2_option_RS2_OPTION_DEPTH_OFFSET,
+
+    LedPower = rs2_option_RS2_OPTION_LED_POWER,
+
+    ZeroOrderEnabled = rs2_option_RS2_OPTION_ZERO_ORDER_ENABLED,
+
+    EnableMapPreservation = rs2_option_RS2_OPTION_ENABLE_MAP_PRESERVATION,
+
+    FreefallDetectionEnabled = rs2_option_RS2_OPTION_FREEFALL_DETECTION_ENABLED,
+
+    AvalanchePhotoDiode = rs2_option_RS2_OPTION_AVALANCHE_PHOTO_DIODE,
+
+    PostProcessingSharpening = rs2_option_RS2_OPTION_POST_PROCESSING_SHARPENING,
+
+    PreProcessingSharpening = rs2_option_RS2_OPTION_PRE_PROCESSING_SHARPENING,
+
+    NoiseFiltering = rs2_option_RS2_OPTION_NOISE_FILTERING,
+
+    InvalidationBypass = rs2_option_RS2_OPTION_INVALIDATION_BYPASS,
+
+    //RS2_OPTION_AMBIENT_LIGHT is a deprecated alias with the same value
+    DigitalGain = rs2_option_RS2_OPTION_DIGITAL_GAIN,
+
+    SensorMode = rs2_option_RS2_OPTION_SENSOR_MODE,
+
+    EmitterAlwaysOn = rs2_option_RS2_OPTION_EMITTER_ALWAYS_ON,
+
+    ThermalCompensation = rs2_option_RS2_OPTION_THERMAL_COMPENSATION,
+
+    TriggerCameraAccuracyHealth = rs2_option_RS2_OPTION_TRIGGER_CAMERA_ACCURACY_HEALTH,
+
+    ResetCameraAccuracyHealth = rs2_option_RS2_OPTION_RESET_CAMERA_ACCURACY_HEALTH,
+
+    HostPerformance = rs2_option_RS2_OPTION_HOST_PERFORMANCE,
+
+    HdrEnabled = rs2_option_RS2_OPTION_HDR_ENABLED,
+
+    SequenceName = rs2_option_RS2_OPTION_SEQUENCE_NAME,
+
+    SequenceSize = rs2_option_RS2_OPTION_SEQUENCE_SIZE,
+
+    SequenceId = rs2_option_RS2_OPTION_SEQUENCE_ID,
+
+    HumidityTemperature = rs2_option_RS2_OPTION_HUMIDITY_TEMPERATURE,
+
+    EnableMaxUsableRange = rs2_option_RS2_OPTION_ENABLE_MAX_USABLE_RANGE,
+
+    AlternateIr = rs2_option_RS2_OPTION_ALTERNATE_IR,
+
+    NoiseEstimation = rs2_option_RS2_OPTION_NOISE_ESTIMATION,
+
+    EnableIrReflectivity = rs2_option_RS2_OPTION_ENABLE_IR_REFLECTIVITY,
+
+    AutoExposureLimit = rs2_option_RS2_OPTION_AUTO_EXPOSURE_LIMIT,
+
+    AutoGainLimit = rs2_option_RS2_OPTION_AUTO_GAIN_LIMIT,
+
+    AutoRxSensitivity = rs2_option_RS2_OPTION_AUTO_RX_SENSITIVITY,
+
+    TransmitterFrequency = rs2_option_RS2_OPTION_TRANSMITTER_FREQUENCY,
+
+    VerticalBinning = rs2_option_RS2_OPTION_VERTICAL_BINNING,
+
+    ReceiverSensitivity = rs2_option_RS2_OPTION_RECEIVER_SENSITIVITY,
+
+    AutoExposureLimitToggle = rs2_option_RS2_OPTION_AUTO_EXPOSURE_LIMIT_TOGGLE,
+
+    AutoGainLimitToggle = rs2_option_RS2_OPTION_AUTO_GAIN_LIMIT_TOGGLE,
+
+    EmitterFrequency = rs2_option_RS2_OPTION_EMITTER_FREQUENCY,
+}
+
+impl fmt::Display for Rs2Option {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = unsafe { CStr::from_ptr(rs2_option_to_string(*self as u32)) };
+        write!(f, "{}", name.to_string_lossy())
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct OptionRange {
+    pub min: f32,
+    pub max: f32,
+    pub step: f32,
+    pub default: f32,
+}
+
+impl OptionRange {
+    pub fn contains(&self, value: f32) -> bool {
+        value >= self.min && value <= self.max
+    }
+}
@@ -0,0 +1,106 @@
+use crate::bindings::*;
+use num_derive::{FromPrimitive, ToPrimitive};
+use num_traits::{FromPrimitive, ToPrimitive};
+use std::{ffi::CStr, fmt};
+
+//Values of Rs2Option::VisualPreset, which set of them applies depends on the camera family
+pub trait VisualPreset: Copy + FromPrimitive + ToPrimitive {
+    //What the camera family reports as Rs2CameraInfo::ProductLine
+    const PRODUCT_LINE: &'static str;
+}
+
+#[repr(u32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Rs400VisualPreset {
+    Custom = rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_CUSTOM,
+
+    Default = rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_DEFAULT,
+
+    Hand = rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_HAND,
+
+    HighAccuracy = rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_HIGH_ACCURACY,
+
+    HighDensity = rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_HIGH_DENSITY,
+
+    MediumDensity = rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_MEDIUM_DENSITY,
+
+    RemoveIrPattern = rs2_rs400_visual_preset_RS2_RS400_VISUAL_PRESET_REMOVE_IR_PATTERN,
+}
+
+#[repr(u32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Sr300VisualPreset {
+    ShortRange = rs2_sr300_visual_preset_RS2_SR300_VISUAL_PRESET_SHORT_RANGE,
+
+    LongRange = rs2_sr300_visual_preset_RS2_SR300_VISUAL_PRESET_LONG_RANGE,
+
+    BackgroundSegmentation =
+        rs2_sr300_visual_preset_RS2_SR300_VISUAL_PRESET_BACKGROUND_SEGMENTATION,
+
+    GestureRecognition = rs2_sr300_visual_preset_RS2_SR300_VISUAL_PRESET_GESTURE_RECOGNITION,
+
+    ObjectScanning = rs2_sr300_visual_preset_RS2_SR300_VISUAL_PRESET_OBJECT_SCANNING,
+
+    FaceAnalytics = rs2_sr300_visual_preset_RS2_SR300_VISUAL_PRESET_FACE_ANALYTICS,
+
+    FaceLogin = rs2_sr300_visual_preset_RS2_SR300_VISUAL_PRESET_FACE_LOGIN,
+
+    GrCursor = rs2_sr300_visual_preset_RS2_SR300_VISUAL_PRESET_GR_CURSOR,
+
+    Default = rs2_sr300_visual_preset_RS2_SR300_VISUAL_PRESET_DEFAULT,
+
+    MidRange = rs2_sr300_visual_preset_RS2_SR300_VISUAL_PRESET_MID_RANGE,
+
+    IrOnly = rs2_sr300_visual_preset_RS2_SR300_VISUAL_PRESET_IR_ONLY,
+}
+
+#[repr(u32)]
+#[derive(FromPrimitive, ToPrimitive, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum L500VisualPreset {
+    Custom = rs2_l500_visual_preset_RS2_L500_VISUAL_PRESET_CUSTOM,
+
+    Default = rs2_l500_visual_preset_RS2_L500_VISUAL_PRESET_DEFAULT,
+
+    NoAmbient = rs2_l500_visual_preset_RS2_L500_VISUAL_PRESET_NO_AMBIENT,
+
+    LowAmbient = rs2_l500_visual_preset_RS2_L500_VISUAL_PRESET_LOW_AMBIENT,
+
+    MaxRange = rs2_l500_visual_preset_RS2_L500_VISUAL_PRESET_MAX_RANGE,
+
+    ShortRange = rs2_l500_visual_preset_RS2_L500_VISUAL_PRESET_SHORT_RANGE,
+
+    Automatic = rs2_l500_visual_preset_RS2_L500_VISUAL_PRESET_AUTOMATIC,
+}
+
+impl VisualPreset for Rs400VisualPreset {
+    const PRODUCT_LINE: &'static str = "D400";
+}
+
+impl VisualPreset for Sr300VisualPreset {
+    const PRODUCT_LINE: &'static str = "SR300";
+}
+
+impl VisualPreset for L500VisualPreset {
+    const PRODUCT_LINE: &'static str = "L500";
+}
+
+impl fmt::Display for Rs400VisualPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = unsafe { CStr::from_ptr(rs2_rs400_visual_preset_to_string(*self as u32)) };
+        write!(f, "{}", name.to_string_lossy())
+    }
+}
+
+impl fmt::Display for Sr300VisualPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = unsafe { CStr::from_ptr(rs2_sr300_visual_preset_to_string(*self as u32)) };
+        write!(f, "{}", name.to_string_lossy())
+    }
+}
+
+impl fmt::Display for L500VisualPreset {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = unsafe { CStr::from_ptr(rs2_l500_visual_preset_to_string(*self as u32)) };
+        write!(f, "{}", name.to_string_lossy())
+    }
+}
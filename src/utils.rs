@@ -1,15 +1,51 @@
-use crate::{bindings::*, ImageData};
-use std::{ffi::CStr, fmt};
+use crate::{
+    bindings::*, camera_info::Rs2CameraInfo, stream::Rs2StreamKind, Device, ImageData,
+    RegionOfInterest,
+};
+use std::{ffi::CStr, fmt, mem::ManuallyDrop};
 
 #[derive(Debug)]
-pub struct RealsenseError {
-    ty: u32,
-    details: String,
+pub enum RealsenseError {
+    //Anything librealsense reported through the rs2_error pointer
+    Api {
+        ty: u32,
+        details: String,
+    },
+    //Context came up fine but there is no camera plugged in
+    NoDevice,
+    //No connected device reports the requested serial number
+    DeviceNotFound {
+        serial: String,
+    },
+    //The requested streams can not all be satisfied by the device at the same time
+    ConfigCannotResolve,
+    //An advanced mode preset that could not be parsed or did not pass validation, one message per problem
+    InvalidPreset(Vec<String>),
+    //The sensor has to be streaming for this, e.g. to know the resolution a region of interest applies to
+    SensorNotStreaming,
+    //The region does not fit inside the width x height stream it is meant for
+    InvalidRegionOfInterest {
+        roi: RegionOfInterest,
+        width: i32,
+        height: i32,
+    },
+    //The stream is not part of what the pipeline or frame was asked about
+    StreamNotFound {
+        stream: Rs2StreamKind,
+        index: i32,
+    },
+    //Calibration uses a distortion model newer than this crate, the raw rs2_distortion value
+    UnknownDistortion(u32),
+    //A visual preset for another camera family, product_line is what the sensor reported
+    PresetProductLineMismatch {
+        expected: &'static str,
+        product_line: Option<String>,
+    },
 }
 
 impl RealsenseError {
     unsafe fn new(error: *mut rs2_error) -> Self {
-        RealsenseError {
+        RealsenseError::Api {
             ty: rs2_get_librealsense_exception_type(error),
             details: CStr::from_ptr(rs2_get_error_message(error))
                 .to_str()
@@ -21,11 +57,45 @@ impl RealsenseError {
 
 impl fmt::Display for RealsenseError {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        write!(
-            f,
-            "After checking the error pointer recieved type: {} with details {}",
-            self.ty, self.details
-        )
+        match self {
+            RealsenseError::Api { ty, details } => write!(
+                f,
+                "After checking the error pointer recieved type: {} with details {}",
+                ty, details
+            ),
+            RealsenseError::NoDevice => write!(f, "No realsense devices are connected"),
+            RealsenseError::DeviceNotFound { serial } => {
+                write!(f, "No connected device has serial number {}", serial)
+            }
+            RealsenseError::ConfigCannotResolve => write!(
+                f,
+                "The pipeline config can not be resolved against the device"
+            ),
+            RealsenseError::InvalidPreset(problems) => {
+                write!(f, "Invalid advanced mode preset: {}", problems.join(", "))
+            }
+            RealsenseError::SensorNotStreaming => write!(f, "The sensor is not streaming"),
+            RealsenseError::InvalidRegionOfInterest { roi, width, height } => write!(
+                f,
+                "Region of interest {:?} does not fit inside the {}x{} stream",
+                roi, width, height
+            ),
+            RealsenseError::StreamNotFound { stream, index } => {
+                write!(f, "No {:?} stream with index {} is active", stream, index)
+            }
+            RealsenseError::UnknownDistortion(model) => {
+                write!(f, "Unknown distortion model {}", model)
+            }
+            RealsenseError::PresetProductLineMismatch {
+                expected,
+                product_line,
+            } => write!(
+                f,
+                "The visual preset is for {} cameras, the sensor reports {}",
+                expected,
+                product_line.as_deref().unwrap_or("no product line")
+            ),
+        }
     }
 }
 
@@ -41,26 +111,23 @@ pub unsafe fn check_error(error: *mut rs2_error) -> Result<(), RealsenseError> {
     }
 }
 
+//Logs name, serial number and firmware version. Fields the device does not report show up as None.
+//The device is only borrowed, whoever passed it in still has to delete it
 pub unsafe fn print_device_info(device: *mut rs2_device) {
-    let mut error = std::ptr::null_mut::<rs2_error>();
+    let device = ManuallyDrop::new(Device { device });
+
+    let info = |info: Rs2CameraInfo| match device.info(info) {
+        Ok(value) => value,
+        Err(err) => {
+            log::warn!("Could not read {:?}: {}", info, err);
+            None
+        }
+    };
 
-    println!(
-        "Using device 0: {:?} Serial number: {:?}, Firmware version: {:?}",
-        CStr::from_ptr(rs2_get_device_info(
-            device,
-            rs2_camera_info_RS2_CAMERA_INFO_NAME,
-            &mut error
-        )),
-        CStr::from_ptr(rs2_get_device_info(
-            device,
-            rs2_camera_info_RS2_CAMERA_INFO_SERIAL_NUMBER,
-            &mut error
-        )),
-        CStr::from_ptr(rs2_get_device_info(
-            device,
-            rs2_camera_info_RS2_CAMERA_INFO_FIRMWARE_VERSION,
-            &mut error
-        ))
+    log::info!(
+        "Using device: {:?} Serial number: {:?}, Firmware version: {:?}",
+        info(Rs2CameraInfo::Name),
+        info(Rs2CameraInfo::SerialNumber),
+        info(Rs2CameraInfo::FirmwareVersion)
     );
-    rs2_free_error(error);
 }